@@ -0,0 +1,91 @@
+use alloy::signers::local::PrivateKeySigner;
+use bsc_discord_sniper::wallets;
+use std::env;
+use std::path::Path;
+use anyhow::{Result, anyhow};
+
+// Usage: keystore create [name] | import [name] [--from-env] | list
+fn main() -> Result<()> {
+    dotenv::dotenv().ok();
+
+    let args: Vec<String> = env::args().skip(1).collect();
+    let name = args.get(1).filter(|a| !a.starts_with("--")).map(String::as_str);
+    let dir = wallets::keystore_dir();
+    // Overwriting a keystore would lose the key for good
+    if let Some(name) = name.filter(|n| dir.join(n).exists()) {
+        if args[0] != "list" {
+            return Err(anyhow!("{} already exists", dir.join(name).display()));
+        }
+    }
+    match args.first().map(String::as_str) {
+        Some("create") => {
+            let password = new_passphrase()?;
+            prepare_dir(&dir)?;
+            let (signer, uuid) = PrivateKeySigner::new_keystore(&dir, &mut rand::thread_rng(), password, name)?;
+            let file = dir.join(name.unwrap_or(&uuid));
+            restrict(&file)?;
+            println!("Created {:?} in {}", signer.address(), file.display());
+        }
+        Some("import") => {
+            let key = if args.iter().any(|a| a == "--from-env") {
+                env::var("PRIVATE_KEY").map_err(|_| anyhow!("PRIVATE_KEY is not set"))?
+            } else {
+                rpassword::prompt_password("Private key: ")?
+            };
+            let signer: PrivateKeySigner = key.trim().parse()?;
+            let password = new_passphrase()?;
+            prepare_dir(&dir)?;
+            let (_, uuid) = PrivateKeySigner::encrypt_keystore(&dir, &mut rand::thread_rng(), signer.to_bytes(), password, name)?;
+            let file = dir.join(name.unwrap_or(&uuid));
+            restrict(&file)?;
+            println!("Imported {:?} into {}", signer.address(), file.display());
+            println!("Remove PRIVATE_KEY from .env; the bot now loads {}", dir.display());
+        }
+        Some("list") => {
+            let password = wallets::passphrase()?;
+            for file in wallets::keystore_files(&dir)? {
+                match PrivateKeySigner::decrypt_keystore(&file, &password) {
+                    Ok(signer) => println!("{:?}  {}", signer.address(), file.display()),
+                    Err(e) => println!("{:<42}  {} ({})", "locked", file.display(), e),
+                }
+            }
+        }
+        _ => return Err(anyhow!("Usage: keystore create [name] | import [name] [--from-env] | list")),
+    }
+    Ok(())
+}
+
+// A configured passphrase, else one typed twice
+fn new_passphrase() -> Result<String> {
+    if let Some(passphrase) = wallets::configured_passphrase()? {
+        return Ok(passphrase);
+    }
+    let passphrase = rpassword::prompt_password("New keystore passphrase: ")?;
+    if passphrase.is_empty() {
+        return Err(anyhow!("Empty passphrase"));
+    }
+    if rpassword::prompt_password("Repeat passphrase: ")? != passphrase {
+        return Err(anyhow!("Passphrases do not match"));
+    }
+    Ok(passphrase)
+}
+
+fn prepare_dir(dir: &Path) -> Result<()> {
+    std::fs::create_dir_all(dir)?;
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        std::fs::set_permissions(dir, std::fs::Permissions::from_mode(0o700))?;
+    }
+    Ok(())
+}
+
+// Keystores are readable by the bot's user only
+fn restrict(file: &Path) -> Result<()> {
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        std::fs::set_permissions(file, std::fs::Permissions::from_mode(0o600))?;
+    }
+    Ok(())
+}
@@ -1,6 +1,6 @@
 use serenity::{
     async_trait,
-    model::{channel::Message, gateway::Ready},
+    model::{channel::Message, gateway::Ready, id::ChannelId},
     prelude::*,
 };
 use std::env;
@@ -8,7 +8,7 @@ use std::sync::Arc;
 use tokio::sync::Mutex;
 use anyhow::Result;
 
-use bsc_discord_sniper::SharedState;
+use bsc_discord_sniper::{SharedState, rules::{self, MessageText, TriggerConfig}};
 
 struct Handler {
     state: Arc<Mutex<SharedState>>,
@@ -20,54 +20,14 @@ impl EventHandler for Handler {
         let state = self.state.lock().await;
         let now = chrono::Local::now().format("%Y-%m-%d %H:%M:%S").to_string();
         
-        // Log every message in the monitored channel
-        if msg.channel_id.get() == state.channel_id {
-            // Check if message content OR any embed contains the triggers
-            let triggers = ["New Tweet from @cz_binance", "New Tweet from @Scratch_XOX"];
-            let keywords = ["book", "publish", "release"];
-            
-            let mut account_matched = triggers.iter().any(|&t| msg.content == t);
-            let mut content_to_search = msg.content.to_lowercase();
-            let mut matched_account = None;
-
-            if account_matched {
-                matched_account = triggers.iter().find(|&t| msg.content == *t).map(|&s| s);
-            }
-            
-            if !account_matched {
-                for (i, embed) in msg.embeds.iter().enumerate() {
-                    if let Some(desc) = &embed.description {
-                        if let Some(matched) = triggers.iter().find(|&t| desc.contains(t)) {
-                            println!("[{}] [Info] Found trigger '{}' in embed {} description", now, matched, i);
-                            account_matched = true;
-                            matched_account = Some(matched);
-                            content_to_search.push_str(&desc.to_lowercase());
-                        }
-                    }
-                    if let Some(title) = &embed.title {
-                        if let Some(matched) = triggers.iter().find(|&t| title.contains(t)) {
-                            println!("[{}] [Info] Found trigger '{}' in embed {} title", now, matched, i);
-                            account_matched = true;
-                            matched_account = Some(matched);
-                            content_to_search.push_str(&title.to_lowercase());
-                        }
-                    }
-                }
-            }
-
-            let mut trigger_found = false;
-            let mut matched_keyword = None;
-            if account_matched {
-                if let Some(keyword) = keywords.iter().find(|&k| content_to_search.contains(k)) {
-                    trigger_found = true;
-                    matched_keyword = Some(*keyword);
-                }
-            }
-
-            if trigger_found {
-                println!("[{}] 🔥 TRIGGER DETECTED: '{}' | Keyword: '{}' (Embed count: {})", 
-                    now, matched_account.unwrap_or("Unknown"), matched_keyword.unwrap_or("None"), msg.embeds.len());
-                let _ = msg.channel_id.say(&ctx.http, format!("🐦 Tweet Monitor: Trigger Detected! Keyword: {}", matched_keyword.unwrap_or("None"))).await;
+        // Log every message in the monitored channels
+        if let Some(channel) = state.triggers.channel(msg.channel_id.get()) {
+            let rules = state.triggers.rules_for(channel);
+            if let Some(trigger) = rules::match_message(channel.feed, &rules, &MessageText::from_discord(&msg)) {
+                println!("[{}] 🔥 TRIGGER DETECTED: '@{}' | Keyword: '{}' | Channel: {} (Embed count: {})",
+                    now, trigger.account, trigger.keyword, channel.name(), msg.embeds.len());
+                let notify = ChannelId::new(state.triggers.notify_channel());
+                let _ = notify.say(&ctx.http, format!("🐦 Tweet Monitor: Trigger Detected! Keyword: {}", trigger.keyword)).await;
             } else {
                 let mut log_name = if !msg.content.is_empty() {
                     msg.content.clone()
@@ -105,7 +65,9 @@ impl EventHandler for Handler {
         println!("[{}] ✅ Tweet Monitor ONLINE as: {}", now, ready.user.name);
         
         let state = self.state.lock().await;
-        println!("[{}] 📡 Monitoring Channel ID: {}", now, state.channel_id);
+        for channel in &state.triggers.channels {
+            println!("[{}] 📡 Monitoring {:?} channel {} (rules: {})", now, channel.feed, channel.name(), channel.rules);
+        }
     }
 }
 
@@ -115,15 +77,9 @@ async fn main() -> Result<()> {
     env_logger::init();
 
     let token = env::var("DISCORD_TOKEN").expect("Expected DISCORD_TOKEN in environment");
-    let channel_id: u64 = env::var("DISCORD_CHANNEL_ID")
-        .expect("Expected DISCORD_CHANNEL_ID in environment")
-        .parse()
-        .expect("Channel ID must be a number");
+    let triggers = TriggerConfig::from_env()?;
 
-    let state = Arc::new(Mutex::new(SharedState {
-        last_cz_tweet_time: None,
-        channel_id,
-    }));
+    let state = Arc::new(Mutex::new(SharedState::new(triggers)));
 
     let handler = Handler {
         state: Arc::clone(&state),
@@ -0,0 +1,33 @@
+use bsc_discord_sniper::{journal, portfolio, wallets};
+use std::env;
+use anyhow::Result;
+
+// Usage: portfolio [--trades]
+#[tokio::main]
+async fn main() -> Result<()> {
+    dotenv::dotenv().ok();
+
+    let snapshot = portfolio::snapshot(&wallets::init()?.addresses()).await?;
+    println!("{}", snapshot.report().replace("**", "").replace('`', ""));
+
+    if env::args().any(|a| a == "--trades") {
+        println!("\nTrades:");
+        for (entry, pnl) in portfolio::trade_pnl(&journal::load(&journal::path())?) {
+            let when = chrono::DateTime::from_timestamp(entry.timestamp, 0)
+                .map(|t| t.with_timezone(&chrono::Local).format("%Y-%m-%d %H:%M:%S").to_string())
+                .unwrap_or_default();
+            println!(
+                "[{}] {:?} {} | {} BNB | {} tokens | {:?}{}",
+                when,
+                entry.side,
+                entry.token,
+                entry.bnb.map_or("?".to_string(), |b| format!("{:.4}", b)),
+                entry.tokens.map_or("?".to_string(), |t| format!("{:.2}", t)),
+                entry.status,
+                pnl.map_or(String::new(), |p| format!(" | realised {:+.4} BNB", p)),
+            );
+        }
+    }
+
+    Ok(())
+}
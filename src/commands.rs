@@ -0,0 +1,380 @@
+use serenity::all::{
+    CommandInteraction, CommandOptionType, Context, CreateCommand,
+    CreateCommandOption, EditInteractionResponse, ResolvedOption, ResolvedValue,
+};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::Mutex;
+use anyhow::{Result, anyhow};
+
+use crate::{SharedState, honeypot, journal, migration::{self, Venue}, monitor, portfolio, reputation, swap, wallets, watchlist, health::{self, HEALTH}, metrics::{self, METRICS}, notify::{Notifier, TradeReport}};
+
+pub fn definitions() -> Vec<CreateCommand> {
+    vec![
+        CreateCommand::new("status").description("Show sniper window, config, wallet and latency"),
+        CreateCommand::new("arm")
+            .description("Open the buy window for a number of seconds")
+            .add_option(
+                CreateCommandOption::new(CommandOptionType::Integer, "seconds", "Window length in seconds")
+                    .required(true)
+                    .min_int_value(1)
+                    .max_int_value(3600),
+            )
+            .add_option(CreateCommandOption::new(
+                CommandOptionType::Boolean,
+                "confirm",
+                "Post candidates with Buy/Skip buttons instead of buying",
+            )),
+        CreateCommand::new("disarm").description("Close any open buy window"),
+        CreateCommand::new("buy")
+            .description("Buy a token now with the configured amount")
+            .add_option(
+                CreateCommandOption::new(CommandOptionType::String, "token", "Token address").required(true),
+            ),
+        CreateCommand::new("sell")
+            .description("Sell a percentage of a token position")
+            .add_option(
+                CreateCommandOption::new(CommandOptionType::String, "token", "Token address").required(true),
+            )
+            .add_option(
+                CreateCommandOption::new(CommandOptionType::Integer, "pct", "Percentage of balance to sell")
+                    .required(true)
+                    .min_int_value(1)
+                    .max_int_value(100),
+            ),
+        CreateCommand::new("config")
+            .description("Change runtime configuration")
+            .add_option(
+                CreateCommandOption::new(CommandOptionType::SubCommand, "set", "Set a configuration value")
+                    .add_sub_option(
+                        CreateCommandOption::new(CommandOptionType::String, "key", "Setting to change")
+                            .required(true)
+                            .add_string_choice("buy_amount", "buy_amount")
+                            .add_string_choice("mode", "mode"),
+                    )
+                    .add_sub_option(
+                        CreateCommandOption::new(CommandOptionType::String, "value", "New value").required(true),
+                    ),
+            ),
+        CreateCommand::new("positions").description("List tokens bought and current balances"),
+        CreateCommand::new("portfolio").description("Show wallet holdings with realised and unrealised PnL"),
+        CreateCommand::new("watch")
+            .description("Manage the watchlist of pre-announced tokens")
+            .add_option(watch_entry("add", "Buy tokens matching this entry as soon as they launch"))
+            .add_option(watch_entry("remove", "Remove an entry from the watchlist"))
+            .add_option(CreateCommandOption::new(CommandOptionType::SubCommand, "list", "Show the watchlist")),
+        CreateCommand::new("creator")
+            .description("Inspect or override token creator reputation")
+            .add_option(creator_action("info", "Show a creator's launches and score"))
+            .add_option(creator_action("allow", "Always buy from this creator"))
+            .add_option(creator_action("deny", "Never buy from this creator"))
+            .add_option(creator_action("unlist", "Remove a creator from the allow and deny lists"))
+            .add_option(creator_action("rug", "Record a rug by this creator")),
+    ]
+}
+
+fn creator_action(name: &str, description: &str) -> CreateCommandOption {
+    CreateCommandOption::new(CommandOptionType::SubCommand, name, description)
+        .add_sub_option(
+            CreateCommandOption::new(CommandOptionType::String, "address", "Creator address").required(true),
+        )
+}
+
+fn watch_entry(name: &str, description: &str) -> CreateCommandOption {
+    CreateCommandOption::new(CommandOptionType::SubCommand, name, description)
+        .add_sub_option(
+            CreateCommandOption::new(CommandOptionType::String, "kind", "What to match on")
+                .required(true)
+                .add_string_choice("name", "name")
+                .add_string_choice("symbol", "symbol")
+                .add_string_choice("creator", "creator"),
+        )
+        .add_sub_option(
+            CreateCommandOption::new(CommandOptionType::String, "value", "Token name, symbol or creator address").required(true),
+        )
+}
+
+pub async fn handle(ctx: &Context, command: &CommandInteraction, state: &Arc<Mutex<SharedState>>) {
+    let now = chrono::Local::now().format("%Y-%m-%d %H:%M:%S").to_string();
+    println!("[{}] [Command] /{} from {} ({})", now, command.data.name, command.user.name, command.user.id);
+
+    let authorised = state.lock().await.admin_ids.contains(&command.user.id.get());
+    if !authorised {
+        let _ = command.defer_ephemeral(&ctx.http).await;
+        let _ = command.edit_response(&ctx.http, EditInteractionResponse::new().content("⛔ You are not allowed to control this bot.")).await;
+        return;
+    }
+
+    // Buys and sells can take longer than Discord's 3s response deadline
+    let _ = command.defer(&ctx.http).await;
+    let reply = match run(ctx, command, state).await {
+        Ok(reply) => reply,
+        Err(e) => format!("❌ `/{}` failed: `{}`", command.data.name, e),
+    };
+    if let Err(e) = command.edit_response(&ctx.http, EditInteractionResponse::new().content(reply)).await {
+        eprintln!("[{}] Failed to respond to /{}: {:?}", now, command.data.name, e);
+    }
+}
+
+async fn run(ctx: &Context, command: &CommandInteraction, state: &Arc<Mutex<SharedState>>) -> Result<String> {
+    let options = command.data.options();
+    match command.data.name.as_str() {
+        "status" => status(state).await,
+        "arm" => {
+            let seconds = int_option(&options, "seconds")?.max(1) as u64;
+            let confirm = bool_option(&options, "confirm").unwrap_or(false);
+            let channel_id = {
+                let mut guard = state.lock().await;
+                guard.armed_until = Some(Instant::now() + Duration::from_secs(seconds));
+                guard.armed_confirm = confirm;
+                // A manual window is not tied to a tweet, so stale targets must not narrow it
+                guard.targets.clear();
+                guard.triggers.notify_channel()
+            };
+            monitor::spawn_sniper(Arc::clone(state), Arc::clone(&ctx.http), channel_id, Duration::from_secs(seconds));
+            if confirm {
+                Ok(format!("🎯 Armed: candidate window OPEN for {}s — buys need a button confirmation.", seconds))
+            } else {
+                Ok(format!("🎯 Armed: buy window OPEN for {}s.", seconds))
+            }
+        }
+        "disarm" => {
+            let mut guard = state.lock().await;
+            guard.armed_until = None;
+            guard.armed_confirm = false;
+            guard.last_cz_tweet_time = None;
+            guard.targets.clear();
+            // Candidates from the closed window must not be bought later
+            guard.pending_confirmations.clear();
+            Ok("🛑 Disarmed: buy window closed.".to_string())
+        }
+        "buy" => {
+            let token = str_option(&options, "token")?;
+            let amount = state.lock().await.buy_amount_bnb;
+            let address = reputation::parse_address(token)?;
+            // Migrated tokens trade on PancakeSwap, where the honeypot simulation applies; curve buys are not checked
+            let venue = migration::venue(address).await?;
+            if venue == Venue::Pancake {
+                if let Some(reason) = honeypot::check(address, swap::to_wei(amount)?).await? {
+                    return Ok(format!("🍯 Not buying `{}`: {}", token, reason));
+                }
+            }
+            let claimed = {
+                let mut guard = state.lock().await;
+                match venue {
+                    Venue::Curve => guard.claim_buy(token),
+                    Venue::Pancake => guard.claim_listing_buy(token),
+                }
+            };
+            if !claimed {
+                return Ok(format!("✅ Already bought `{}`; not buying again.", token));
+            }
+            metrics::inc(&METRICS.buys_attempted);
+            let result = match venue {
+                Venue::Curve => swap::execute_swap(token, amount).await,
+                Venue::Pancake => swap::execute_pancake_buy(token, amount).await,
+            };
+            let outcomes = match result {
+                Ok(outcomes) => outcomes,
+                Err(e) => {
+                    metrics::inc(&METRICS.buys_failed);
+                    state.lock().await.release_buy(token, venue == Venue::Pancake);
+                    return Err(e);
+                }
+            };
+            let notifier = Notifier::new(Arc::clone(&ctx.http), command.channel_id.get());
+            for outcome in &outcomes {
+                METRICS.bnb_spent(outcome.funds);
+                notifier.trade(TradeReport::buy_leg(token, outcome)).await;
+            }
+            let txs: Vec<String> = outcomes.iter().map(|o| format!("`{:?}`", o.tx_hash)).collect();
+            Ok(format!("🚀 Buy sent for `{}` ({} BNB)\nTx: {}", token, amount, txs.join(", ")))
+        }
+        "sell" => {
+            let token = str_option(&options, "token")?;
+            let pct = int_option(&options, "pct")?;
+            let outcomes = swap::execute_sell(token, pct.clamp(1, 100) as u64).await?;
+            let notifier = Notifier::new(Arc::clone(&ctx.http), command.channel_id.get());
+            for outcome in &outcomes {
+                notifier.trade(TradeReport::sell_leg(token, outcome)).await;
+            }
+            let amount = outcomes.iter().fold(alloy::primitives::U256::ZERO, |total, o| total + o.amount);
+            let txs: Vec<String> = outcomes.iter().map(|o| format!("`{:?}`", o.tx_hash)).collect();
+            Ok(format!("💸 Sell sent for {}% of `{}` ({} units)\nTx: {}", pct, token, amount, txs.join(", ")))
+        }
+        "config" => {
+            let set = options.iter().find(|o| o.name == "set").ok_or_else(|| anyhow!("Unknown config action"))?;
+            let ResolvedValue::SubCommand(sub) = &set.value else {
+                return Err(anyhow!("Malformed config command"));
+            };
+            let key = str_option(sub, "key")?;
+            let value = str_option(sub, "value")?;
+            match key {
+                "buy_amount" => {
+                    let amount: f64 = value.parse().map_err(|_| anyhow!("'{}' is not a number", value))?;
+                    if amount <= 0.0 {
+                        return Err(anyhow!("Buy amount must be positive"));
+                    }
+                    state.lock().await.buy_amount_bnb = amount;
+                    Ok(format!("⚙️ buy_amount set to {} BNB", amount))
+                }
+                "mode" => {
+                    let confirm = match value.to_lowercase().as_str() {
+                        "confirm" => true,
+                        "auto" => false,
+                        _ => return Err(anyhow!("mode must be 'auto' or 'confirm'")),
+                    };
+                    state.lock().await.confirm_mode = confirm;
+                    Ok(format!("⚙️ mode set to {}", if confirm { "confirm" } else { "auto" }))
+                }
+                other => Err(anyhow!("Unknown config key '{}'", other)),
+            }
+        }
+        "positions" => positions().await,
+        "portfolio" => Ok(portfolio::snapshot(&wallets::wallets()?.addresses()).await?.report()),
+        "watch" => {
+            let Some(action) = options.first() else {
+                return Err(anyhow!("Missing watch action"));
+            };
+            let ResolvedValue::SubCommand(sub) = &action.value else {
+                return Err(anyhow!("Malformed watch command"));
+            };
+            if action.name == "list" {
+                return Ok(format!("👀 **Watchlist**\n{}", state.lock().await.watchlist.describe()));
+            }
+
+            let kind = watchlist::Kind::parse(str_option(sub, "kind")?)?;
+            let value = str_option(sub, "value")?;
+            let (changed, channel_id) = {
+                let mut guard = state.lock().await;
+                let changed = match action.name {
+                    "add" => guard.watchlist.add(kind, value)?,
+                    "remove" => guard.watchlist.remove(kind, value)?,
+                    other => return Err(anyhow!("Unknown watch action '{}'", other)),
+                };
+                if changed {
+                    guard.watchlist.save(&watchlist::path())?;
+                }
+                (changed, guard.triggers.notify_channel())
+            };
+            monitor::ensure_watchlist_monitor(Arc::clone(state), Arc::clone(&ctx.http), channel_id).await;
+            match (action.name, changed) {
+                ("add", true) => Ok(format!("👀 Watching for {:?} `{}`", kind, value)),
+                ("add", false) => Ok(format!("Already watching {:?} `{}`", kind, value)),
+                (_, true) => Ok(format!("🗑️ Stopped watching {:?} `{}`", kind, value)),
+                (_, false) => Ok(format!("{:?} `{}` was not on the watchlist", kind, value)),
+            }
+        }
+        "creator" => {
+            let Some(action) = options.first() else {
+                return Err(anyhow!("Missing creator action"));
+            };
+            let ResolvedValue::SubCommand(sub) = &action.value else {
+                return Err(anyhow!("Malformed creator command"));
+            };
+            let creator = reputation::parse_address(str_option(sub, "address")?)?;
+            let mut guard = state.lock().await;
+            let changed = match action.name {
+                "info" => return Ok(format!("🧑‍🍳 {}", guard.reputation.describe(&creator))),
+                "allow" => guard.reputation.set_listing(creator, Some(true)),
+                "deny" => guard.reputation.set_listing(creator, Some(false)),
+                "unlist" => guard.reputation.set_listing(creator, None),
+                "rug" => {
+                    guard.reputation.mark_rugged(creator);
+                    true
+                }
+                other => return Err(anyhow!("Unknown creator action '{}'", other)),
+            };
+            if changed {
+                guard.reputation.save(&reputation::path())?;
+            }
+            Ok(format!("{}\n{}", if changed { "✅ Updated" } else { "Nothing to change" }, guard.reputation.describe(&creator)))
+        }
+        other => Err(anyhow!("Unknown command '{}'", other)),
+    }
+}
+
+async fn status(state: &Arc<Mutex<SharedState>>) -> Result<String> {
+    let now = Instant::now();
+    let (window, confirm, armed_for, amount, targets, latency) = {
+        let guard = state.lock().await;
+        (
+            guard.window_open(now),
+            guard.confirm_required(now),
+            guard.armed_until.and_then(|until| until.checked_duration_since(now)),
+            guard.buy_amount_bnb,
+            guard.targets.clone(),
+            guard.latency.report(),
+        )
+    };
+    let health = HEALTH.report(&health::Thresholds::from_env());
+    let wallet = match wallets::wallets() {
+        Ok(set) if set.wallets.len() > 1 => format!("{:?} (+{} more, {:?})", set.primary().address(), set.wallets.len() - 1, set.strategy),
+        Ok(set) => format!("{:?}", set.primary().address()),
+        Err(_) => "not configured".to_string(),
+    };
+
+    let mut lines = vec![
+        format!("**Window:** {}", if window { "🟢 OPEN" } else { "⚪ closed" }),
+        format!("**Armed for:** {}", armed_for.map_or("-".to_string(), |d| format!("{}s", d.as_secs()))),
+        format!("**Mode:** {}", if confirm { "confirm (buttons)" } else { "auto" }),
+        format!("**Buy amount:** {} BNB", amount),
+        format!("**Targets:** {}", if targets.is_empty() { "any Chinese name".to_string() } else { targets.join(", ") }),
+        format!("**Wallet:** `{}`", wallet),
+        format!("**Balance:** {}", health.wallet_balance_bnb.map_or("unknown".to_string(), |b| format!("{:.4} BNB", b))),
+        format!("**Health:** {}", if health.healthy { "✅ healthy".to_string() } else { format!("⚠️ {}", health.problems.join("; ")) }),
+    ];
+    if !latency.is_empty() {
+        lines.push(format!("**Latency:**\n```\n{}\n```", latency));
+    }
+    Ok(lines.join("\n"))
+}
+
+async fn positions() -> Result<String> {
+    let entries = journal::load(&journal::path())?;
+    let positions = journal::positions(&entries);
+    if positions.is_empty() {
+        return Ok("No trades in the journal yet.".to_string());
+    }
+
+    let mut lines = vec!["**Positions:**".to_string()];
+    for position in positions {
+        let units = swap::token_balance(&position.token).await.ok();
+        let balance = units.map_or("?".to_string(), |units| format!("{:.2}", swap::to_units(units)));
+        // What the balance would fetch if sold now, on the curve or on PancakeSwap after migration
+        let value = match (units, position.token.parse()) {
+            (Some(units), Ok(token)) if !units.is_zero() => match migration::quote_sell(token, units).await {
+                Ok(bnb) => format!(" | worth {:.4} BNB", swap::to_units(bnb)),
+                Err(_) => String::new(),
+            },
+            _ => String::new(),
+        };
+        lines.push(format!(
+            "`{}` — balance {}{} | {} buys / {} sells | spent {:.4} BNB",
+            position.token, balance, value, position.buys, position.sells, position.bnb_spent
+        ));
+    }
+    Ok(lines.join("\n"))
+}
+
+fn str_option<'a>(options: &'a [ResolvedOption<'a>], name: &str) -> Result<&'a str> {
+    match options.iter().find(|o| o.name == name).map(|o| &o.value) {
+        Some(ResolvedValue::String(s)) => Ok(s),
+        _ => Err(anyhow!("Missing option '{}'", name)),
+    }
+}
+
+fn bool_option(options: &[ResolvedOption<'_>], name: &str) -> Option<bool> {
+    match options.iter().find(|o| o.name == name).map(|o| &o.value) {
+        Some(ResolvedValue::Boolean(b)) => Some(*b),
+        _ => None,
+    }
+}
+
+fn int_option(options: &[ResolvedOption<'_>], name: &str) -> Result<i64> {
+    match options.iter().find(|o| o.name == name).map(|o| &o.value) {
+        Some(ResolvedValue::Integer(i)) => Ok(*i),
+        _ => Err(anyhow!("Missing option '{}'", name)),
+    }
+}
+
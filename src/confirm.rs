@@ -0,0 +1,197 @@
+use serenity::all::{
+    ButtonStyle, ChannelId, ComponentInteraction, Context, CreateActionRow, CreateButton,
+    CreateInteractionResponse, CreateInteractionResponseMessage, CreateMessage, EditInteractionResponse,
+};
+use std::env;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::Mutex;
+
+use crate::{SharedState, decoder::TokenCreate, swap, metrics::{self, METRICS}, notify::{Notifier, TradeReport}};
+
+const BUY_PREFIX: &str = "confirm-buy";
+const SKIP_PREFIX: &str = "confirm-skip";
+
+// BNB amounts offered as buttons on each candidate
+pub fn confirm_amounts() -> Vec<f64> {
+    let amounts: Vec<f64> = env::var("CONFIRM_AMOUNTS")
+        .unwrap_or_default()
+        .split(',')
+        .filter_map(|a| a.trim().parse().ok())
+        .filter(|a| *a > 0.0)
+        .collect();
+    if amounts.is_empty() { vec![0.1, 0.5] } else { amounts }
+}
+
+// How long a posted candidate can still be bought
+pub fn ttl() -> Duration {
+    Duration::from_secs(env::var("CONFIRM_TTL_SECS").ok().and_then(|v| v.parse().ok()).unwrap_or(120))
+}
+
+// A candidate waiting for a Buy or Skip click
+#[derive(Debug, Clone)]
+pub struct Pending {
+    pub token: TokenCreate,
+    pub posted_at: Instant,
+}
+
+impl Pending {
+    pub fn expired(&self, now: Instant, ttl: Duration) -> bool {
+        now.duration_since(self.posted_at) >= ttl
+    }
+}
+
+enum Action {
+    Buy(f64, String),
+    Skip(String),
+}
+
+fn parse_custom_id(custom_id: &str) -> Option<Action> {
+    let mut parts = custom_id.split(':');
+    match (parts.next()?, parts.next(), parts.next(), parts.next()) {
+        (BUY_PREFIX, Some(amount), Some(token), None) => Some(Action::Buy(amount.parse().ok()?, token.to_string())),
+        (SKIP_PREFIX, Some(token), None, None) => Some(Action::Skip(token.to_string())),
+        _ => None,
+    }
+}
+
+pub async fn post_candidate(
+    http: &Arc<serenity::http::Http>,
+    channel_id: u64,
+    state: &Arc<Mutex<SharedState>>,
+    token: &TokenCreate,
+) {
+    let token_hex = token.token_hex();
+    {
+        let mut guard = state.lock().await;
+        let now = Instant::now();
+        guard.expire_confirmations(now);
+        guard.pending_confirmations.insert(token_hex.clone(), Pending { token: token.clone(), posted_at: now });
+    }
+
+    let mut buttons: Vec<CreateButton> = confirm_amounts()
+        .into_iter()
+        .map(|amount| {
+            CreateButton::new(format!("{}:{}:{}", BUY_PREFIX, amount, token_hex))
+                .label(format!("Buy {}", amount))
+                .style(ButtonStyle::Success)
+        })
+        .collect();
+    buttons.push(CreateButton::new(format!("{}:{}", SKIP_PREFIX, token_hex)).label("Skip").style(ButtonStyle::Secondary));
+
+    let content = format!(
+        "🎯 **Candidate token**\nName: **{}**\nSymbol: **{}**\nCreator: `{:?}`\nAddress: `{}`",
+        token.name, token.symbol, token.creator, token_hex
+    );
+    let message = CreateMessage::new().content(content).components(vec![CreateActionRow::Buttons(buttons)]);
+    if let Err(e) = ChannelId::new(channel_id).send_message(http, message).await {
+        eprintln!("Failed to post candidate {}: {:?}", token_hex, e);
+    }
+}
+
+pub async fn handle_button(ctx: &Context, component: &ComponentInteraction, state: &Arc<Mutex<SharedState>>) {
+    let Some(action) = parse_custom_id(&component.data.custom_id) else {
+        return;
+    };
+    let now = chrono::Local::now().format("%Y-%m-%d %H:%M:%S").to_string();
+    let user = &component.user.name;
+
+    let ephemeral = |text: &str| {
+        CreateInteractionResponse::Message(CreateInteractionResponseMessage::new().content(text).ephemeral(true))
+    };
+
+    let token_hex = match &action {
+        Action::Buy(_, token) | Action::Skip(token) => token.clone(),
+    };
+    let claimed = {
+        let mut guard = state.lock().await;
+        if !guard.admin_ids.contains(&component.user.id.get()) {
+            let _ = component.create_response(&ctx.http, ephemeral("⛔ You are not allowed to trade with this bot.")).await;
+            return;
+        }
+        // Removing the entry makes sure two quick clicks cannot buy twice
+        guard.pending_confirmations.remove(&token_hex)
+    };
+    let Some(pending) = claimed else {
+        let _ = component.create_response(&ctx.http, ephemeral("This candidate was already handled or has expired.")).await;
+        return;
+    };
+
+    let original = component.message.content.clone();
+    let update = |text: String| {
+        CreateInteractionResponse::UpdateMessage(
+            CreateInteractionResponseMessage::new().content(text).components(vec![]),
+        )
+    };
+    if pending.expired(Instant::now(), ttl()) {
+        println!("[{}] [Confirm] {} clicked expired candidate {}", now, user, token_hex);
+        let _ = component.create_response(&ctx.http, update(format!("{}\n⌛ Expired: not buying.", original))).await;
+        return;
+    }
+    let candidate = pending.token;
+
+    match action {
+        Action::Skip(_) => {
+            println!("[{}] [Confirm] {} skipped {}", now, user, token_hex);
+            let _ = component.create_response(&ctx.http, update(format!("{}\n⏭️ Skipped by {}", original, user))).await;
+        }
+        Action::Buy(amount, _) => {
+            // The sniper, the watchlist or another posted candidate may have bought it already
+            if !state.lock().await.claim_buy(&token_hex) {
+                println!("[{}] [Confirm] {} approved {} but it was already bought", now, user, token_hex);
+                let _ = component.create_response(&ctx.http, update(format!("{}\n✅ Already bought; not buying again.", original))).await;
+                return;
+            }
+            println!("[{}] [Confirm] {} approved buy of {} BNB for {}", now, user, amount, token_hex);
+            let _ = component.create_response(&ctx.http, update(format!("{}\n⏳ Buying {} BNB (approved by {})...", original, amount, user))).await;
+
+            let notifier = Notifier::new(Arc::clone(&ctx.http), component.channel_id.get());
+            metrics::inc(&METRICS.buys_attempted);
+            let result = match swap::execute_swap(&token_hex, amount).await {
+                Ok(outcomes) => {
+                    for outcome in &outcomes {
+                        METRICS.bnb_spent(outcome.funds);
+                        notifier.trade(TradeReport {
+                            name: Some(candidate.name.clone()),
+                            symbol: Some(candidate.symbol.clone()),
+                            creator: Some(candidate.creator),
+                            ..TradeReport::buy_leg(&token_hex, outcome)
+                        }).await;
+                    }
+                    let txs: Vec<String> = outcomes.iter().map(|o| format!("`{:?}`", o.tx_hash)).collect();
+                    format!("{}\n🚀 Buy sent: {} BNB (approved by {})\nTx: {}", original, amount, user, txs.join(", "))
+                }
+                Err(e) => {
+                    metrics::inc(&METRICS.buys_failed);
+                    eprintln!("[{}] Confirmed swap failed: {:?}", now, e);
+                    notifier.alert(&format!("Confirmed buy failed for {}", token_hex), &format!("{:?}", e)).await;
+                    format!("{}\n❌ Buy failed: `{}`", original, e)
+                }
+            };
+            let _ = component.edit_response(&ctx.http, EditInteractionResponse::new().content(result)).await;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_custom_id() {
+        let token = "0x4444444444444444444444444444444444444444";
+        match parse_custom_id(&format!("confirm-buy:0.5:{}", token)) {
+            Some(Action::Buy(amount, t)) => assert_eq!((amount, t.as_str()), (0.5, token)),
+            _ => panic!("expected buy"),
+        }
+        assert!(matches!(parse_custom_id(&format!("confirm-skip:{}", token)), Some(Action::Skip(_))));
+        assert!(parse_custom_id("confirm-buy:abc:0x1").is_none());
+        assert!(parse_custom_id("something-else").is_none());
+
+        let posted_at = Instant::now();
+        let token = TokenCreate { creator: Default::default(), token: Default::default(), name: "币安人生".to_string(), symbol: "BNBLIFE".to_string() };
+        let pending = Pending { token, posted_at };
+        assert!(!pending.expired(posted_at + Duration::from_secs(59), Duration::from_secs(60)));
+        assert!(pending.expired(posted_at + Duration::from_secs(60), Duration::from_secs(60)));
+    }
+}
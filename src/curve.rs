@@ -0,0 +1,268 @@
+use alloy::{
+    primitives::{address, Address, U256},
+    providers::{ProviderBuilder, RootProvider},
+    transports::BoxTransport,
+    sol,
+};
+use std::env;
+use std::str::FromStr;
+use anyhow::{Result, anyhow};
+
+use crate::metrics::METRICS;
+use crate::swap::to_units;
+
+sol!(
+    #[sol(rpc)]
+    contract TokenManagerHelper3 {
+        function getTokenInfo(address token) external view returns (
+            uint256 version,
+            address tokenManager,
+            address quote,
+            uint256 lastPrice,
+            uint256 tradingFeeRate,
+            uint256 minTradingFee,
+            uint256 launchTime,
+            uint256 offers,
+            uint256 maxOffers,
+            uint256 funds,
+            uint256 maxFunds,
+            bool liquidityAdded
+        );
+
+        function tryBuy(address token, uint256 amount, uint256 funds) external view returns (
+            address tokenManager,
+            address quote,
+            uint256 estimatedAmount,
+            uint256 estimatedCost,
+            uint256 estimatedFee,
+            uint256 amountMsgValue,
+            uint256 amountApproval,
+            uint256 amountFunds
+        );
+
+        function trySell(address token, uint256 amount) external view returns (
+            address tokenManager,
+            address quote,
+            uint256 funds,
+            uint256 fee
+        );
+    }
+);
+
+// four.meme's read-only helper in front of every TokenManager version
+const TOKEN_MANAGER_HELPER_3: Address = address!("F251F83e40a78868FcfA3FA4599Dad6494E46034");
+
+// Trading fees are quoted in basis points
+const FEE_DENOMINATOR: f64 = 10_000.0;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct CurveState {
+    pub token_manager: Address,
+    // Zero when the curve trades against BNB
+    pub quote: Address,
+    // BNB per whole token at the last trade
+    pub price: U256,
+    pub fee_rate_bps: U256,
+    pub min_fee: U256,
+    pub launch_time: u64,
+    // Tokens still for sale on the curve, out of `max_offers`
+    pub offers: U256,
+    pub max_offers: U256,
+    // BNB raised so far, out of `max_funds` at which the token migrates to PancakeSwap
+    pub funds: U256,
+    pub max_funds: U256,
+    pub migrated: bool,
+}
+
+impl CurveState {
+    // 0..=100, how far the curve is toward migration
+    pub fn progress_pct(&self) -> f64 {
+        if self.migrated {
+            return 100.0;
+        }
+        let max = to_units(self.max_funds);
+        if max <= 0.0 {
+            return 0.0;
+        }
+        (to_units(self.funds) / max * 100.0).clamp(0.0, 100.0)
+    }
+
+    pub fn fee_pct(&self) -> f64 {
+        self.fee_rate_bps.saturating_to::<u64>() as f64 / FEE_DENOMINATOR * 100.0
+    }
+
+    pub fn summary(&self) -> String {
+        format!(
+            "price {:.10} BNB | raised {:.3}/{:.3} BNB ({:.1}%) | {:.0} tokens left | fee {:.2}%{}",
+            to_units(self.price),
+            to_units(self.funds),
+            to_units(self.max_funds),
+            self.progress_pct(),
+            to_units(self.offers),
+            self.fee_pct(),
+            if self.migrated { " | migrated" } else { "" },
+        )
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct BuyQuote {
+    pub tokens: U256,
+    // BNB that goes into the curve, and the fee on top
+    pub cost: U256,
+    pub fee: U256,
+    // What to send with the transaction
+    pub msg_value: U256,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct SellQuote {
+    // BNB back, before the fee is taken
+    pub funds: U256,
+    pub fee: U256,
+}
+
+impl SellQuote {
+    pub fn net(&self) -> U256 {
+        self.funds.saturating_sub(self.fee)
+    }
+}
+
+pub struct Curve {
+    rpc_url: String,
+    helper: Address,
+}
+
+impl Curve {
+    pub fn new(rpc_url: &str, helper: Address) -> Self {
+        Curve { rpc_url: rpc_url.to_string(), helper }
+    }
+
+    pub fn from_env() -> Result<Self> {
+        let helper = match env::var("FOUR_MEME_HELPER") {
+            Ok(helper) => Address::from_str(&helper)?,
+            Err(_) => TOKEN_MANAGER_HELPER_3,
+        };
+        Ok(Curve::new(&env::var("BSC_RPC_URL")?, helper))
+    }
+
+    async fn helper(&self) -> Result<TokenManagerHelper3::TokenManagerHelper3Instance<BoxTransport, RootProvider<BoxTransport>>> {
+        let provider = ProviderBuilder::new().on_builtin(&self.rpc_url).await?;
+        Ok(TokenManagerHelper3::new(self.helper, provider))
+    }
+
+    pub async fn state(&self, token: Address) -> Result<CurveState> {
+        let info = self.helper().await?.getTokenInfo(token).call().await
+            .inspect_err(|_| METRICS.rpc_error("eth_call"))?;
+        if info.tokenManager.is_zero() {
+            return Err(anyhow!("{:?} is not a four.meme token", token));
+        }
+        Ok(CurveState {
+            token_manager: info.tokenManager,
+            quote: info.quote,
+            price: info.lastPrice,
+            fee_rate_bps: info.tradingFeeRate,
+            min_fee: info.minTradingFee,
+            launch_time: info.launchTime.saturating_to(),
+            offers: info.offers,
+            max_offers: info.maxOffers,
+            funds: info.funds,
+            max_funds: info.maxFunds,
+            migrated: info.liquidityAdded,
+        })
+    }
+
+    // Tokens `bnb` buys right now, fee included
+    pub async fn quote_buy(&self, token: Address, bnb: U256) -> Result<BuyQuote> {
+        let quote = self.helper().await?.tryBuy(token, U256::ZERO, bnb).call().await
+            .inspect_err(|_| METRICS.rpc_error("eth_call"))?;
+        Ok(BuyQuote {
+            tokens: quote.estimatedAmount,
+            cost: quote.estimatedCost,
+            fee: quote.estimatedFee,
+            msg_value: quote.amountMsgValue,
+        })
+    }
+
+    // BNB that selling `tokens` back into the curve returns right now
+    pub async fn quote_sell(&self, token: Address, tokens: U256) -> Result<SellQuote> {
+        let quote = self.helper().await?.trySell(token, tokens).call().await
+            .inspect_err(|_| METRICS.rpc_error("eth_call"))?;
+        Ok(SellQuote { funds: quote.funds, fee: quote.fee })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy::sol_types::SolCall;
+
+    fn bnb(milli: u64) -> U256 {
+        U256::from(milli) * U256::from(1_000_000_000_000_000u64)
+    }
+
+    // Answers eth_call like a helper on a curve priced at 1 BNB per 1,000,000 tokens with a 1% fee
+    fn mock_call(input: &[u8]) -> Vec<u8> {
+        let manager = Address::repeat_byte(0x5c);
+        if let Ok(call) = TokenManagerHelper3::getTokenInfoCall::abi_decode(input, true) {
+            let known = call.token == Address::repeat_byte(0x44);
+            return TokenManagerHelper3::getTokenInfoCall::abi_encode_returns(&(
+                U256::from(2),
+                if known { manager } else { Address::ZERO },
+                Address::ZERO,
+                U256::from(1_000_000_000_000u64),
+                U256::from(100),
+                U256::ZERO,
+                U256::from(1_700_000_000u64),
+                bnb(800_000_000),
+                bnb(800_000_000),
+                bnb(6_000),
+                bnb(24_000),
+                false,
+            ));
+        }
+        if let Ok(call) = TokenManagerHelper3::tryBuyCall::abi_decode(input, true) {
+            let fee = call.funds / U256::from(100);
+            let cost = call.funds - fee;
+            return TokenManagerHelper3::tryBuyCall::abi_encode_returns(&(
+                manager, Address::ZERO, cost * U256::from(1_000_000), cost, fee, call.funds, U256::ZERO, call.funds,
+            ));
+        }
+        let call = TokenManagerHelper3::trySellCall::abi_decode(input, true).unwrap();
+        let funds = call.amount / U256::from(1_000_000);
+        TokenManagerHelper3::trySellCall::abi_encode_returns(&(manager, Address::ZERO, funds, funds / U256::from(100)))
+    }
+
+    async fn mock_rpc() -> String {
+        crate::testutil::mock_http(|request| {
+            let request: serde_json::Value = serde_json::from_str(&request.body).unwrap();
+            let input = request["params"][0]["input"].as_str()
+                .or(request["params"][0]["data"].as_str())
+                .unwrap();
+            let output = mock_call(&hex::decode(input.trim_start_matches("0x")).unwrap());
+            serde_json::json!({"jsonrpc": "2.0", "id": request["id"], "result": format!("0x{}", hex::encode(output))}).to_string()
+        }).await
+    }
+
+    #[tokio::test]
+    async fn test_curve_quotes_against_mock_helper() {
+        let curve = Curve::new(&mock_rpc().await, TOKEN_MANAGER_HELPER_3);
+        let token = Address::repeat_byte(0x44);
+
+        let state = curve.state(token).await.unwrap();
+        assert_eq!(state.token_manager, Address::repeat_byte(0x5c));
+        assert_eq!(state.progress_pct(), 25.0);
+        assert_eq!(state.fee_pct(), 1.0);
+        assert!(!state.migrated);
+        assert!(curve.state(Address::repeat_byte(0x45)).await.is_err());
+
+        let buy = curve.quote_buy(token, bnb(1_000)).await.unwrap();
+        assert_eq!(buy.fee, bnb(10));
+        assert_eq!(buy.tokens, bnb(990) * U256::from(1_000_000));
+        assert_eq!(buy.msg_value, bnb(1_000));
+
+        let sell = curve.quote_sell(token, buy.tokens).await.unwrap();
+        assert_eq!(sell.funds, bnb(990));
+        assert_eq!(sell.net(), bnb(990) - bnb(990) / U256::from(100));
+    }
+}
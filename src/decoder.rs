@@ -1,25 +1,70 @@
-use alloy::primitives::U256;
+use alloy::primitives::{address, Address, U256};
 use anyhow::{Result, anyhow};
+use std::env;
 
-pub fn decode_custom_log(data: &[u8]) -> Result<(String, String)> {
+// four.meme TokenManager2, the only contract whose TokenCreate logs we trust by default
+const TOKEN_MANAGER_2: Address = address!("5c952063c7fc8610FFDB798152D69F0B9550762b");
+
+#[derive(Debug, Clone)]
+pub struct TokenCreate {
+    pub creator: Address,
+    pub token: Address,
+    pub name: String,
+    pub symbol: String,
+}
+
+impl TokenCreate {
+    pub fn token_hex(&self) -> String {
+        format!("0x{}", hex::encode(self.token))
+    }
+}
+
+// Official four.meme factories from FOUR_MEME_FACTORIES (comma separated), TokenManager2 if unset
+pub fn factories() -> Result<Vec<Address>> {
+    let Ok(raw) = env::var("FOUR_MEME_FACTORIES") else {
+        return Ok(vec![TOKEN_MANAGER_2]);
+    };
+    let factories = raw.split(',')
+        .map(str::trim)
+        .filter(|f| !f.is_empty())
+        .map(|f| f.parse().map_err(|_| anyhow!("'{}' in FOUR_MEME_FACTORIES is not an address", f)))
+        .collect::<Result<Vec<Address>>>()?;
+    if factories.is_empty() {
+        return Err(anyhow!("FOUR_MEME_FACTORIES is set but lists no addresses"));
+    }
+    Ok(factories)
+}
+
+// Any contract can emit a look-alike TokenCreate; only the factories' logs are real launches
+pub fn decode_token_create(emitter: Address, data: &[u8], factories: &[Address]) -> Result<TokenCreate> {
+    if !factories.contains(&emitter) {
+        return Err(anyhow!("TokenCreate emitted by {:?}, which is not an official factory", emitter));
+    }
+    decode_custom_log(data)
+}
+
+pub fn decode_custom_log(data: &[u8]) -> Result<TokenCreate> {
     if data.len() < 32 * 8 {
         return Err(anyhow!("Data too short for header"));
     }
 
-    // word0: address A (skip 12 bytes padding)
-    // word1: address B (skip 12 bytes padding)
+    // word0: creator address (skip 12 bytes padding)
+    // word1: token address (skip 12 bytes padding)
     // word2: uint256
-    // word3: offset A
-    // word4: offset B
+    // word3: offset of name
+    // word4: offset of symbol
     // word5-7: uint256
 
+    let creator = Address::from_slice(&data[12..32]);
+    let token = Address::from_slice(&data[44..64]);
+
     let offset_a = parse_uint256(&data[32*3..32*4])?.to::<usize>();
     let offset_b = parse_uint256(&data[32*4..32*5])?.to::<usize>();
 
-    let string_a = extract_string(data, offset_a)?;
-    let string_b = extract_string(data, offset_b)?;
+    let name = extract_string(data, offset_a)?;
+    let symbol = extract_string(data, offset_b)?;
 
-    Ok((string_a, string_b))
+    Ok(TokenCreate { creator, token, name, symbol })
 }
 
 fn parse_uint256(chunk: &[u8]) -> Result<U256> {
@@ -49,10 +94,10 @@ fn extract_string(data: &[u8], offset: usize) -> Result<String> {
 pub fn contains_chinese(s: &str) -> bool {
     s.chars().any(|c| {
         // Range for common Chinese characters (Unified Ideographs)
-        (c >= '\u{4E00}' && c <= '\u{9FFF}') ||
+        ('\u{4E00}'..='\u{9FFF}').contains(&c) ||
         // Extended ranges if necessary
-        (c >= '\u{3400}' && c <= '\u{4DBF}') ||
-        (c >= '\u{20000}' && c <= '\u{2A6DF}')
+        ('\u{3400}'..='\u{4DBF}').contains(&c) ||
+        ('\u{20000}'..='\u{2A6DF}').contains(&c)
     })
 }
 
@@ -60,6 +105,58 @@ pub fn contains_chinese(s: &str) -> bool {
 mod tests {
     use super::*;
 
+    fn encode_word(value: u64) -> [u8; 32] {
+        let mut word = [0u8; 32];
+        word[24..].copy_from_slice(&value.to_be_bytes());
+        word
+    }
+
+    fn encode_string(out: &mut Vec<u8>, s: &str) {
+        out.extend_from_slice(&encode_word(s.len() as u64));
+        let mut padded = s.as_bytes().to_vec();
+        padded.resize(s.len().div_ceil(32) * 32, 0);
+        out.extend_from_slice(&padded);
+    }
+
+    // Builds a TokenCreate(creator, token, requestId, name, symbol, totalSupply, launchTime, launchFee) payload
+    fn token_create_data(creator: Address, token: Address, name: &str, symbol: &str) -> Vec<u8> {
+        let mut data = Vec::new();
+        let mut word = [0u8; 32];
+        word[12..].copy_from_slice(creator.as_slice());
+        data.extend_from_slice(&word);
+        word[12..].copy_from_slice(token.as_slice());
+        data.extend_from_slice(&word);
+        data.extend_from_slice(&encode_word(7));
+        let name_offset = 32 * 8;
+        let symbol_offset = name_offset + 32 + name.len().div_ceil(32) * 32;
+        data.extend_from_slice(&encode_word(name_offset as u64));
+        data.extend_from_slice(&encode_word(symbol_offset as u64));
+        for _ in 0..3 {
+            data.extend_from_slice(&encode_word(0));
+        }
+        encode_string(&mut data, name);
+        encode_string(&mut data, symbol);
+        data
+    }
+
+    #[test]
+    fn test_decode_token_create() {
+        let creator = Address::repeat_byte(0x11);
+        let token = Address::repeat_byte(0x44);
+        let decoded = decode_custom_log(&token_create_data(creator, token, "币安人生", "BNBLIFE")).unwrap();
+        assert_eq!(decoded.creator, creator);
+        assert_eq!(decoded.token, token);
+        assert_eq!(decoded.name, "币安人生");
+        assert_eq!(decoded.symbol, "BNBLIFE");
+        assert_eq!(decoded.token_hex(), format!("0x{}", "44".repeat(20)));
+
+        assert!(decode_custom_log(&[0u8; 64]).is_err());
+
+        let data = token_create_data(creator, token, "币安人生", "BNBLIFE");
+        assert!(decode_token_create(TOKEN_MANAGER_2, &data, &[TOKEN_MANAGER_2]).is_ok());
+        assert!(decode_token_create(Address::repeat_byte(0x66), &data, &[TOKEN_MANAGER_2]).is_err());
+    }
+
     #[test]
     fn test_contains_chinese() {
         assert!(contains_chinese("Hello 世界"));
@@ -0,0 +1,268 @@
+use async_trait::async_trait;
+use serenity::{
+    model::{channel::Message, gateway::Ready, event::{MessageUpdateEvent, ResumedEvent}, application::{Command, Interaction}, id::{ChannelId, GuildId, MessageId}},
+    gateway::{ConnectionStage, ShardStageUpdateEvent},
+    prelude::*,
+};
+use std::collections::{HashMap, VecDeque};
+use std::env;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::{mpsc, Mutex};
+use anyhow::Result;
+
+use crate::{SharedState, commands, confirm, health::HEALTH, rules::{self, EmbedText, MessageText, RuleSet, WatchedChannel}, triggers::{TriggerEvent, TriggerSource}, tweet::Tweet};
+
+// Tweet relay bots, Telegram bridges and news bots posting into watched Discord channels.
+// The same gateway connection also serves slash commands and confirm buttons.
+pub struct DiscordSource {
+    token: String,
+    state: Arc<Mutex<SharedState>>,
+}
+
+impl DiscordSource {
+    pub fn new(token: String, state: Arc<Mutex<SharedState>>) -> Self {
+        Self { token, state }
+    }
+}
+
+#[async_trait]
+impl TriggerSource for DiscordSource {
+    fn name(&self) -> String {
+        "discord".to_string()
+    }
+
+    async fn run(self: Box<Self>, events: mpsc::Sender<TriggerEvent>) -> Result<()> {
+        let handler = Handler { state: self.state, events, recent: Default::default() };
+        let intents = GatewayIntents::GUILD_MESSAGES | GatewayIntents::MESSAGE_CONTENT;
+        let mut client = Client::builder(&self.token, intents)
+            .event_handler(handler)
+            .await?;
+
+        // The gateway can stall without a stage change, so also poll the heartbeat state directly
+        let shard_manager = client.shard_manager.clone();
+        tokio::spawn(async move {
+            loop {
+                tokio::time::sleep(Duration::from_secs(10)).await;
+                let runners = shard_manager.runners.lock().await;
+                if let Some(info) = runners.values().next() {
+                    HEALTH.discord_stage(&info.stage.to_string(), info.stage == ConnectionStage::Connected);
+                    HEALTH.discord_heartbeat(info.latency);
+                }
+            }
+        });
+
+        client.start().await?;
+        Ok(())
+    }
+}
+
+struct Handler {
+    state: Arc<Mutex<SharedState>>,
+    events: mpsc::Sender<TriggerEvent>,
+    recent: std::sync::Mutex<RecentMessages>,
+}
+
+// How many watched-channel messages are kept so later edits can be matched in full
+const RECENT_MESSAGES: usize = 256;
+
+struct SeenMessage {
+    text: MessageText,
+    triggered: bool,
+}
+
+// Relays often post a bare message and add the tweet embed in an edit
+#[derive(Default)]
+struct RecentMessages {
+    order: VecDeque<MessageId>,
+    messages: HashMap<MessageId, SeenMessage>,
+}
+
+impl RecentMessages {
+    fn get(&self, id: MessageId) -> Option<&SeenMessage> {
+        self.messages.get(&id)
+    }
+
+    fn store(&mut self, id: MessageId, text: MessageText) {
+        if let Some(seen) = self.messages.get_mut(&id) {
+            seen.text = text;
+            return;
+        }
+        self.order.push_back(id);
+        self.messages.insert(id, SeenMessage { text, triggered: false });
+        if self.order.len() > RECENT_MESSAGES {
+            if let Some(old) = self.order.pop_front() {
+                self.messages.remove(&old);
+            }
+        }
+    }
+
+    fn mark_triggered(&mut self, id: MessageId) {
+        if let Some(seen) = self.messages.get_mut(&id) {
+            seen.triggered = true;
+        }
+    }
+}
+
+// Discord snowflakes carry the creation time in ms since the Discord epoch
+fn created_ms(id: MessageId) -> i64 {
+    (id.get() >> 22) as i64 + 1_420_070_400_000
+}
+
+impl Handler {
+    async fn watched(&self, channel_id: ChannelId) -> Option<(WatchedChannel, RuleSet)> {
+        let state = self.state.lock().await;
+        let channel = state.triggers.channel(channel_id.get())?.clone();
+        let rules = state.triggers.rules_for(&channel);
+        Some((channel, rules))
+    }
+
+    // Runs the matcher for a new or edited message and forwards a match to the pipeline
+    // posted_ms is when the text went out: the message's creation, or its edit for an update
+    async fn evaluate(&self, channel: &WatchedChannel, rules: &RuleSet, id: MessageId, text: MessageText, received_at: Instant, posted_ms: i64) {
+        let now = chrono::Local::now().format("%Y-%m-%d %H:%M:%S").to_string();
+        let relay_delay_ms = chrono::Utc::now().timestamp_millis() - posted_ms;
+
+        let trigger = rules::match_message(channel.feed, rules, &text);
+        let summary = text.summary();
+        let event = trigger.map(|trigger| {
+            let tweet = Tweet::from_message(&text);
+            println!("[{}] [Tweet] {:?} by @{} (id {})", now, tweet.kind,
+                tweet.author.as_deref().unwrap_or("?"), tweet.id.as_deref().unwrap_or("?"));
+            TriggerEvent {
+                source: format!("discord:{}", channel.name()),
+                account: trigger.account,
+                keyword: trigger.keyword,
+                text: tweet.text,
+                url: tweet.url,
+                tweet_id: tweet.id,
+                received_at,
+                relay_delay_ms: Some(relay_delay_ms),
+            }
+        });
+
+        {
+            let mut recent = self.recent.lock().unwrap();
+            recent.store(id, text);
+            if event.is_some() {
+                recent.mark_triggered(id);
+            }
+        }
+
+        match event {
+            Some(event) => {
+                if self.events.send(event).await.is_err() {
+                    eprintln!("[{}] Trigger pipeline closed; dropping Discord trigger", now);
+                }
+            }
+            None => println!("[{}] [Debug] Ignored ({}): '{}'", now, channel.name(), summary),
+        }
+    }
+}
+
+#[async_trait]
+impl EventHandler for Handler {
+    async fn message(&self, _ctx: Context, msg: Message) {
+        // Capture receipt time before anything else so the latency breakdown starts here
+        let received_at = Instant::now();
+        HEALTH.discord_event();
+        let now = chrono::Local::now().format("%Y-%m-%d %H:%M:%S").to_string();
+
+        // Debug: Log every message for troubleshooting with timestamp
+        println!("[{}] [Debug] Message Context -> ChannelID: {}, Content: '{}', Embeds: {}",
+            now, msg.channel_id, msg.content, msg.embeds.len());
+
+        let Some((channel, rules)) = self.watched(msg.channel_id).await else {
+            return;
+        };
+        self.evaluate(&channel, &rules, msg.id, MessageText::from_discord(&msg), received_at, created_ms(msg.id)).await;
+    }
+
+    async fn message_update(&self, _ctx: Context, _old: Option<Message>, new: Option<Message>, event: MessageUpdateEvent) {
+        let received_at = Instant::now();
+        HEALTH.discord_event();
+        let now = chrono::Local::now().format("%Y-%m-%d %H:%M:%S").to_string();
+
+        let Some((channel, rules)) = self.watched(event.channel_id).await else {
+            return;
+        };
+
+        // Updates only carry the changed fields; fill the rest in from the original message
+        let text = {
+            let recent = self.recent.lock().unwrap();
+            let original = recent.get(event.id);
+            if original.is_some_and(|seen| seen.triggered) {
+                println!("[{}] [Debug] Edit of message {} ignored; it already triggered", now, event.id);
+                return;
+            }
+            match &new {
+                Some(message) => MessageText::from_discord(message),
+                None => {
+                    let mut text = original.map(|seen| seen.text.clone()).unwrap_or_default();
+                    if let Some(content) = &event.content {
+                        text.content = content.clone();
+                    }
+                    if let Some(embeds) = &event.embeds {
+                        text.embeds = embeds.iter().map(EmbedText::from_discord).collect();
+                    }
+                    text
+                }
+            }
+        };
+
+        println!("[{}] [Debug] Message Update -> ChannelID: {}, Content: '{}', Embeds: {}",
+            now, event.channel_id, text.content, text.embeds.len());
+        // The relay delay of an edit counts from the edit, not from the original post
+        let posted_ms = event.edited_timestamp.or_else(|| new.as_ref().and_then(|m| m.edited_timestamp))
+            .map(|edited| (edited.unix_timestamp_nanos() / 1_000_000) as i64)
+            .unwrap_or_else(|| created_ms(event.id));
+        self.evaluate(&channel, &rules, event.id, text, received_at, posted_ms).await;
+    }
+
+    async fn ready(&self, ctx: Context, ready: Ready) {
+        HEALTH.discord_stage("Connected", true);
+        HEALTH.discord_event();
+        let now = chrono::Local::now().format("%Y-%m-%d %H:%M:%S").to_string();
+        println!("[{}] ✅ Bot connected as: {}", now, ready.user.name);
+        println!("[{}] 🔍 ID: {}", now, ready.user.id);
+
+        let state = self.state.lock().await;
+        for channel in &state.triggers.channels {
+            println!("[{}] 📡 Monitoring {:?} channel {} (rules: {})", now, channel.feed, channel.name(), channel.rules);
+        }
+        println!("[{}] 📣 Notifications go to channel {}", now, state.triggers.notify_channel());
+
+        // Notify Discord that the bot is alive
+        let channel = ChannelId::new(state.triggers.notify_channel());
+        let _ = channel.say(&ctx.http, "online").await;
+
+        // Guild commands update instantly; global ones can take up to an hour to appear
+        let registered = match env::var("DISCORD_GUILD_ID").ok().and_then(|id| id.parse::<u64>().ok()) {
+            Some(guild_id) => GuildId::new(guild_id).set_commands(&ctx.http, commands::definitions()).await,
+            None => Command::set_global_commands(&ctx.http, commands::definitions()).await,
+        };
+        match registered {
+            Ok(cmds) => println!("[{}] 🛠️ Registered {} slash commands ({} admins)", now, cmds.len(), state.admin_ids.len()),
+            Err(e) => eprintln!("[{}] Failed to register slash commands: {:?}", now, e),
+        }
+    }
+
+    async fn interaction_create(&self, ctx: Context, interaction: Interaction) {
+        match interaction {
+            Interaction::Command(command) => commands::handle(&ctx, &command, &self.state).await,
+            Interaction::Component(component) => confirm::handle_button(&ctx, &component, &self.state).await,
+            _ => {}
+        }
+    }
+
+    async fn resume(&self, _ctx: Context, _event: ResumedEvent) {
+        HEALTH.discord_stage("Connected", true);
+        HEALTH.discord_event();
+    }
+
+    async fn shard_stage_update(&self, _ctx: Context, event: ShardStageUpdateEvent) {
+        let now = chrono::Local::now().format("%Y-%m-%d %H:%M:%S").to_string();
+        println!("[{}] Discord gateway stage: {} -> {}", now, event.old, event.new);
+        HEALTH.discord_stage(&event.new.to_string(), event.new == ConnectionStage::Connected);
+    }
+}
@@ -0,0 +1,339 @@
+use async_trait::async_trait;
+use futures_util::StreamExt;
+use serde::Deserialize;
+use serde_json::Value;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::time::{Duration, Instant};
+use tokio::sync::mpsc;
+use tokio_tungstenite::tungstenite::Message;
+use anyhow::{Result, anyhow};
+
+use crate::rules::{self, RuleSet, TriggerConfig};
+use crate::triggers::{TriggerEvent, TriggerSource};
+
+// How many recent tweet ids a feed remembers, in case the server ignores the cursor
+const SEEN_IDS: usize = 512;
+
+// A direct tweet stream: ws:// or wss:// for push feeds, http(s):// for long-poll
+#[derive(Debug, Clone, Deserialize)]
+pub struct FeedConfig {
+    pub url: String,
+    #[serde(default)]
+    pub label: Option<String>,
+    #[serde(default = "rules::default_rule_set")]
+    pub rules: String,
+    #[serde(default)]
+    pub fields: FieldMapping,
+    // Extra request headers, e.g. an API key
+    #[serde(default)]
+    pub headers: HashMap<String, String>,
+    // Pause between long-poll requests; the server is expected to hold each one open
+    #[serde(default = "default_poll_interval_ms")]
+    pub poll_interval_ms: u64,
+}
+
+fn default_poll_interval_ms() -> u64 {
+    250
+}
+
+impl FeedConfig {
+    pub fn name(&self) -> String {
+        self.label.clone().unwrap_or_else(|| self.url.clone())
+    }
+}
+
+// Dotted JSON paths to the tweet fields, so any provider's payload can be read
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct FieldMapping {
+    // Path to the array of tweets; unset means the payload is a tweet or an array of tweets
+    pub items: Option<String>,
+    pub id: String,
+    pub author: String,
+    pub text: String,
+    // Unix seconds/milliseconds or RFC 3339
+    pub created_at: Option<String>,
+    pub url: Option<String>,
+    // Long-poll query parameter carrying the last seen id
+    pub cursor_param: Option<String>,
+}
+
+impl Default for FieldMapping {
+    fn default() -> Self {
+        Self {
+            items: None,
+            id: "id".to_string(),
+            author: "author".to_string(),
+            text: "text".to_string(),
+            created_at: Some("created_at".to_string()),
+            url: Some("url".to_string()),
+            cursor_param: Some("since_id".to_string()),
+        }
+    }
+}
+
+pub struct FeedSource {
+    config: FeedConfig,
+    rules: RuleSet,
+    seen: HashSet<String>,
+    seen_order: VecDeque<String>,
+    cursor: Option<String>,
+}
+
+// One source per configured feed
+pub fn sources(config: &TriggerConfig) -> Vec<Box<dyn TriggerSource>> {
+    config.feeds.iter()
+        .map(|feed| Box::new(FeedSource::new(feed.clone(), config.rule_set(&feed.rules))) as Box<dyn TriggerSource>)
+        .collect()
+}
+
+impl FeedSource {
+    pub fn new(config: FeedConfig, rules: RuleSet) -> Self {
+        Self { config, rules, seen: HashSet::new(), seen_order: VecDeque::new(), cursor: None }
+    }
+
+    // Ok on a clean close; the reconnect backoff resets as soon as a session is established
+    async fn run_websocket(&mut self, events: &mpsc::Sender<TriggerEvent>, backoff: &mut Duration) -> Result<()> {
+        let mut request = tokio_tungstenite::tungstenite::client::IntoClientRequest::into_client_request(self.config.url.as_str())?;
+        for (name, value) in &self.config.headers {
+            request.headers_mut().insert(
+                tokio_tungstenite::tungstenite::http::HeaderName::from_bytes(name.as_bytes())?,
+                value.parse()?,
+            );
+        }
+        let (mut socket, _) = tokio_tungstenite::connect_async(request).await?;
+        let now = chrono::Local::now().format("%Y-%m-%d %H:%M:%S").to_string();
+        println!("[{}] 📡 Tweet feed {} connected", now, self.config.name());
+        *backoff = Duration::from_secs(1);
+
+        while let Some(message) = socket.next().await {
+            let payload = match message? {
+                Message::Text(text) => text,
+                Message::Binary(bytes) => String::from_utf8_lossy(&bytes).into_owned(),
+                Message::Close(_) => break,
+                _ => continue,
+            };
+            let received_at = Instant::now();
+            match serde_json::from_str::<Value>(&payload) {
+                Ok(value) => self.handle_payload(&value, received_at, events).await?,
+                Err(e) => eprintln!("[{}] Tweet feed {} sent invalid JSON: {}", now, self.config.name(), e),
+            }
+        }
+        let now = chrono::Local::now().format("%Y-%m-%d %H:%M:%S").to_string();
+        println!("[{}] Tweet feed {} closed by the server; reconnecting", now, self.config.name());
+        Ok(())
+    }
+
+    async fn poll_once(&mut self, client: &reqwest::Client, events: &mpsc::Sender<TriggerEvent>) -> Result<()> {
+        let mut request = client.get(&self.config.url);
+        if let (Some(param), Some(cursor)) = (&self.config.fields.cursor_param, &self.cursor) {
+            request = request.query(&[(param, cursor)]);
+        }
+        for (name, value) in &self.config.headers {
+            request = request.header(name, value);
+        }
+        let value: Value = request.send().await?.error_for_status()?.json().await?;
+        self.handle_payload(&value, Instant::now(), events).await
+    }
+
+    async fn handle_payload(&mut self, payload: &Value, received_at: Instant, events: &mpsc::Sender<TriggerEvent>) -> Result<()> {
+        let items = match &self.config.fields.items {
+            Some(path) => lookup(payload, path).cloned().unwrap_or(Value::Null),
+            None => payload.clone(),
+        };
+        let items = match items {
+            Value::Array(items) => items,
+            Value::Null => Vec::new(),
+            item => vec![item],
+        };
+
+        for item in &items {
+            if let Some(event) = self.parse_item(item, received_at) {
+                events.send(event).await.map_err(|_| anyhow!("Trigger pipeline closed"))?;
+            }
+        }
+        Ok(())
+    }
+
+    fn parse_item(&mut self, item: &Value, received_at: Instant) -> Option<TriggerEvent> {
+        let fields = &self.config.fields;
+        let id = lookup(item, &fields.id).and_then(as_string)?;
+        let author = lookup(item, &fields.author).and_then(as_string).unwrap_or_default();
+        let text = lookup(item, &fields.text).and_then(as_string).unwrap_or_default();
+        let posted_ms = fields.created_at.as_deref().and_then(|p| lookup(item, p)).and_then(timestamp_ms);
+        let url = fields.url.as_deref().and_then(|p| lookup(item, p)).and_then(as_string);
+
+        // Providers differ on newest- or oldest-first, so the cursor tracks the highest id seen
+        if self.cursor.as_deref().is_none_or(|cursor| newer_id(&id, cursor)) {
+            self.cursor = Some(id.clone());
+        }
+        if !self.seen.insert(id.clone()) {
+            return None;
+        }
+        self.seen_order.push_back(id.clone());
+        if self.seen_order.len() > SEEN_IDS {
+            if let Some(old) = self.seen_order.pop_front() {
+                self.seen.remove(&old);
+            }
+        }
+
+        let now = chrono::Local::now().format("%Y-%m-%d %H:%M:%S").to_string();
+        let Some(trigger) = rules::match_tweet(&self.rules, &author, &text) else {
+            println!("[{}] [TweetFeed] Ignored tweet from @{} via {}", now, author, self.config.name());
+            return None;
+        };
+        Some(TriggerEvent {
+            source: format!("feed:{}", self.config.name()),
+            account: trigger.account,
+            keyword: trigger.keyword,
+            text,
+            url,
+            tweet_id: Some(id),
+            received_at,
+            relay_delay_ms: posted_ms.map(|posted| chrono::Utc::now().timestamp_millis() - posted),
+        })
+    }
+}
+
+#[async_trait]
+impl TriggerSource for FeedSource {
+    fn name(&self) -> String {
+        format!("feed:{}", self.config.name())
+    }
+
+    async fn run(mut self: Box<Self>, events: mpsc::Sender<TriggerEvent>) -> Result<()> {
+        let websocket = self.config.url.starts_with("ws://") || self.config.url.starts_with("wss://");
+        let client = reqwest::Client::builder().timeout(Duration::from_secs(90)).build()?;
+        let mut backoff = Duration::from_secs(1);
+
+        loop {
+            let result = if websocket {
+                self.run_websocket(&events, &mut backoff).await
+            } else {
+                self.poll_once(&client, &events).await
+            };
+            match result {
+                Ok(()) => {
+                    backoff = Duration::from_secs(1);
+                    tokio::time::sleep(Duration::from_millis(self.config.poll_interval_ms)).await;
+                }
+                Err(e) => {
+                    if events.is_closed() {
+                        return Err(e);
+                    }
+                    let now = chrono::Local::now().format("%Y-%m-%d %H:%M:%S").to_string();
+                    eprintln!("[{}] Tweet feed {} error: {:?}. Retrying in {:?}...", now, self.config.name(), e, backoff);
+                    tokio::time::sleep(backoff).await;
+                    backoff = (backoff * 2).min(Duration::from_secs(30));
+                }
+            }
+        }
+    }
+}
+
+// Numeric ids (tweet snowflakes) compare by value; anything else is taken in arrival order
+fn newer_id(id: &str, cursor: &str) -> bool {
+    match (id.parse::<u128>(), cursor.parse::<u128>()) {
+        (Ok(id), Ok(cursor)) => id > cursor,
+        _ => true,
+    }
+}
+
+fn lookup<'a>(value: &'a Value, path: &str) -> Option<&'a Value> {
+    path.split('.').try_fold(value, |v, key| match v {
+        Value::Array(items) => items.get(key.parse::<usize>().ok()?),
+        _ => v.get(key),
+    })
+}
+
+fn as_string(value: &Value) -> Option<String> {
+    match value {
+        Value::String(s) => Some(s.clone()),
+        Value::Number(n) => Some(n.to_string()),
+        _ => None,
+    }
+}
+
+fn timestamp_ms(value: &Value) -> Option<i64> {
+    match value {
+        // Anything below 1e12 is taken as seconds
+        Value::Number(n) => n.as_i64().map(|t| if t < 1_000_000_000_000 { t * 1000 } else { t }),
+        Value::String(s) => chrono::DateTime::parse_from_rfc3339(s).ok().map(|t| t.timestamp_millis()),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::net::TcpListener;
+    use futures_util::SinkExt;
+
+    fn feed(url: String, fields: FieldMapping) -> FeedSource {
+        let config = FeedConfig { url, label: Some("mock".to_string()), rules: "default".to_string(), fields, headers: HashMap::new(), poll_interval_ms: 10 };
+        FeedSource::new(config, RuleSet::default())
+    }
+
+    #[tokio::test]
+    async fn test_long_poll_feed() {
+        let (requests_tx, mut requests) = mpsc::unbounded_channel();
+        let mut first = true;
+        let url = crate::testutil::mock_http(move |request| {
+            let _ = requests_tx.send(request.request_line.clone());
+            let body = if first {
+                // Newest first, as most tweet APIs return them
+                r#"{"data":[
+                    {"id":"101","user":{"screen_name":"cz_binance"},"full_text":"I will publish my book","created_at":1700000000},
+                    {"id":"100","user":{"screen_name":"elonmusk"},"full_text":"book release"}
+                ]}"#
+            } else {
+                r#"{"data":[]}"#
+            };
+            first = false;
+            body.to_string()
+        }).await;
+
+        let fields = FieldMapping {
+            items: Some("data".to_string()),
+            author: "user.screen_name".to_string(),
+            text: "full_text".to_string(),
+            ..FieldMapping::default()
+        };
+        let (tx, mut rx) = mpsc::channel(8);
+        tokio::spawn(Box::new(feed(format!("{}/tweets", url), fields)).run(tx));
+
+        let event = tokio::time::timeout(Duration::from_secs(5), rx.recv()).await.unwrap().unwrap();
+        assert_eq!(event.account, "cz_binance");
+        assert_eq!(event.keyword, "book*");
+        assert_eq!(event.source, "feed:mock");
+        assert!(event.relay_delay_ms.unwrap() > 0);
+
+        // The next poll carries the cursor of the last tweet seen
+        assert_eq!(requests.recv().await.unwrap(), "GET /tweets HTTP/1.1");
+        assert_eq!(requests.recv().await.unwrap(), "GET /tweets?since_id=101 HTTP/1.1");
+    }
+
+    #[tokio::test]
+    async fn test_websocket_feed() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            let (conn, _) = listener.accept().await.unwrap();
+            let mut socket = tokio_tungstenite::accept_async(conn).await.unwrap();
+            let tweet = r#"{"id":7,"author":"@Scratch_XOX","text":"New RELEASE today","url":"https://x.com/Scratch_XOX/status/7"}"#;
+            // Duplicate deliveries are dropped by id
+            socket.send(Message::Text(tweet.to_string())).await.unwrap();
+            socket.send(Message::Text(tweet.to_string())).await.unwrap();
+            tokio::time::sleep(Duration::from_secs(5)).await;
+        });
+
+        let (tx, mut rx) = mpsc::channel(8);
+        tokio::spawn(Box::new(feed(format!("ws://{}", addr), FieldMapping::default())).run(tx));
+
+        let event = tokio::time::timeout(Duration::from_secs(5), rx.recv()).await.unwrap().unwrap();
+        assert_eq!(event.account, "Scratch_XOX");
+        assert_eq!(event.keyword, "release*");
+        assert_eq!(event.url.as_deref(), Some("https://x.com/Scratch_XOX/status/7"));
+        assert!(tokio::time::timeout(Duration::from_millis(300), rx.recv()).await.is_err());
+    }
+}
@@ -0,0 +1,332 @@
+use alloy::{
+    primitives::U256,
+    providers::{Provider, ProviderBuilder},
+};
+use serde::Serialize;
+use std::env;
+use std::sync::atomic::{AtomicBool, AtomicI64, AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::Duration;
+
+use crate::{swap, metrics::METRICS};
+
+pub struct Health {
+    discord_stage: Mutex<String>,
+    discord_connected: AtomicBool,
+    // Unix milliseconds; 0 means "never"
+    discord_changed_ms: AtomicI64,
+    discord_event_ms: AtomicI64,
+    discord_latency_ms: AtomicI64,
+    last_log_ms: AtomicI64,
+    active_monitors: AtomicU64,
+    last_block: AtomicU64,
+    last_block_ms: AtomicI64,
+    rpc_reachable: AtomicBool,
+    rpc_ok_ms: AtomicI64,
+    wallet_balance: Mutex<Option<U256>>,
+    started_ms: AtomicI64,
+}
+
+pub static HEALTH: Health = Health::new();
+
+#[derive(Debug, Clone)]
+pub struct Thresholds {
+    pub discord_stale: Duration,
+    pub block_stale: Duration,
+    pub rpc_stale: Duration,
+    pub log_stale: Duration,
+    pub startup_grace: Duration,
+}
+
+impl Thresholds {
+    pub fn from_env() -> Self {
+        let secs = |key: &str, default: u64| {
+            Duration::from_secs(env::var(key).ok().and_then(|v| v.parse().ok()).unwrap_or(default))
+        };
+        Self {
+            discord_stale: secs("WATCHDOG_DISCORD_STALE_SECS", 120),
+            block_stale: secs("WATCHDOG_BLOCK_STALE_SECS", 60),
+            rpc_stale: secs("WATCHDOG_RPC_STALE_SECS", 60),
+            log_stale: secs("WATCHDOG_LOG_STALE_SECS", 300),
+            startup_grace: secs("WATCHDOG_STARTUP_GRACE_SECS", 60),
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct HealthReport {
+    pub healthy: bool,
+    pub ready: bool,
+    pub discord_stage: String,
+    pub discord_connected: bool,
+    pub discord_heartbeat_ms: Option<i64>,
+    pub secs_since_discord_event: Option<f64>,
+    pub secs_since_last_log: Option<f64>,
+    pub active_monitors: u64,
+    pub last_block: Option<u64>,
+    pub secs_since_last_block: Option<f64>,
+    pub rpc_reachable: bool,
+    pub secs_since_rpc_ok: Option<f64>,
+    pub wallet_balance_bnb: Option<f64>,
+    pub problems: Vec<String>,
+}
+
+fn now_ms() -> i64 {
+    chrono::Utc::now().timestamp_millis()
+}
+
+fn age_secs(at_ms: i64, now: i64) -> Option<f64> {
+    (at_ms > 0).then(|| (now - at_ms).max(0) as f64 / 1000.0)
+}
+
+impl Health {
+    const fn new() -> Self {
+        Self {
+            discord_stage: Mutex::new(String::new()),
+            discord_connected: AtomicBool::new(false),
+            discord_changed_ms: AtomicI64::new(0),
+            discord_event_ms: AtomicI64::new(0),
+            discord_latency_ms: AtomicI64::new(-1),
+            last_log_ms: AtomicI64::new(0),
+            active_monitors: AtomicU64::new(0),
+            last_block: AtomicU64::new(0),
+            last_block_ms: AtomicI64::new(0),
+            rpc_reachable: AtomicBool::new(false),
+            rpc_ok_ms: AtomicI64::new(0),
+            wallet_balance: Mutex::new(None),
+            started_ms: AtomicI64::new(0),
+        }
+    }
+
+    pub fn mark_started(&self) {
+        self.started_ms.store(now_ms(), Ordering::Relaxed);
+    }
+
+    pub fn discord_stage(&self, stage: &str, connected: bool) {
+        let mut current = self.discord_stage.lock().unwrap();
+        if *current != stage {
+            *current = stage.to_string();
+            self.discord_changed_ms.store(now_ms(), Ordering::Relaxed);
+        }
+        self.discord_connected.store(connected, Ordering::Relaxed);
+    }
+
+    pub fn discord_heartbeat(&self, latency: Option<Duration>) {
+        let ms = latency.map_or(-1, |l| l.as_millis() as i64);
+        self.discord_latency_ms.store(ms, Ordering::Relaxed);
+    }
+
+    pub fn discord_event(&self) {
+        self.discord_event_ms.store(now_ms(), Ordering::Relaxed);
+    }
+
+    pub fn log_received(&self, block: Option<u64>) {
+        self.last_log_ms.store(now_ms(), Ordering::Relaxed);
+        if let Some(block) = block {
+            self.block_seen(block);
+        }
+    }
+
+    pub fn monitor_started(&self) {
+        self.active_monitors.fetch_add(1, Ordering::Relaxed);
+        // Give a fresh monitor a full log_stale period before judging it
+        self.last_log_ms.fetch_max(now_ms(), Ordering::Relaxed);
+    }
+
+    pub fn monitor_stopped(&self) {
+        self.active_monitors.fetch_sub(1, Ordering::Relaxed);
+    }
+
+    pub fn block_seen(&self, block: u64) {
+        if self.last_block.fetch_max(block, Ordering::Relaxed) < block {
+            self.last_block_ms.store(now_ms(), Ordering::Relaxed);
+        }
+    }
+
+    pub fn rpc_result(&self, ok: bool) {
+        self.rpc_reachable.store(ok, Ordering::Relaxed);
+        if ok {
+            self.rpc_ok_ms.store(now_ms(), Ordering::Relaxed);
+        }
+    }
+
+    pub fn wallet_balance(&self, wei: U256) {
+        *self.wallet_balance.lock().unwrap() = Some(wei);
+    }
+
+    pub fn report(&self, thresholds: &Thresholds) -> HealthReport {
+        let now = now_ms();
+        let last_block = self.last_block.load(Ordering::Relaxed);
+        let latency = self.discord_latency_ms.load(Ordering::Relaxed);
+        let mut report = HealthReport {
+            healthy: true,
+            ready: false,
+            discord_stage: self.discord_stage.lock().unwrap().clone(),
+            discord_connected: self.discord_connected.load(Ordering::Relaxed),
+            discord_heartbeat_ms: (latency >= 0).then_some(latency),
+            secs_since_discord_event: age_secs(self.discord_event_ms.load(Ordering::Relaxed), now),
+            secs_since_last_log: age_secs(self.last_log_ms.load(Ordering::Relaxed), now),
+            active_monitors: self.active_monitors.load(Ordering::Relaxed),
+            last_block: (last_block > 0).then_some(last_block),
+            secs_since_last_block: age_secs(self.last_block_ms.load(Ordering::Relaxed), now),
+            rpc_reachable: self.rpc_reachable.load(Ordering::Relaxed),
+            secs_since_rpc_ok: age_secs(self.rpc_ok_ms.load(Ordering::Relaxed), now),
+            wallet_balance_bnb: self.wallet_balance.lock().unwrap()
+                .map(swap::to_units),
+            problems: Vec::new(),
+        };
+
+        let in_grace = age_secs(self.started_ms.load(Ordering::Relaxed), now)
+            .is_none_or(|age| age < thresholds.startup_grace.as_secs_f64());
+        let discord_down_for = age_secs(self.discord_changed_ms.load(Ordering::Relaxed), now);
+        evaluate(&mut report, thresholds, in_grace, discord_down_for);
+        report
+    }
+}
+
+// Fills in problems/healthy/ready from the collected report fields
+fn evaluate(report: &mut HealthReport, thresholds: &Thresholds, in_grace: bool, discord_down_for: Option<f64>) {
+    let stale = |age: Option<f64>, limit: Duration| age.map_or(!in_grace, |a| a > limit.as_secs_f64());
+
+    if !report.discord_connected && stale(discord_down_for, thresholds.discord_stale) {
+        report.problems.push(format!("Discord gateway not connected (stage: '{}')", report.discord_stage));
+    }
+    if stale(report.secs_since_last_block, thresholds.block_stale) {
+        report.problems.push("No new BSC block seen".to_string());
+    }
+    if stale(report.secs_since_rpc_ok, thresholds.rpc_stale) {
+        report.problems.push("BSC RPC unreachable".to_string());
+    }
+    if report.active_monitors > 0 && stale(report.secs_since_last_log, thresholds.log_stale) {
+        report.problems.push("Log monitor running but no logs received".to_string());
+    }
+
+    report.healthy = report.problems.is_empty();
+    report.ready = report.discord_connected && report.rpc_reachable;
+}
+
+// Periodically checks RPC reachability, latest block and the wallet balance
+pub async fn run_probe(interval: Duration) {
+    let rpc_url = match env::var("BSC_RPC_URL") {
+        Ok(url) => url,
+        Err(_) => {
+            eprintln!("Health probe disabled: BSC_RPC_URL not set");
+            return;
+        }
+    };
+    let wallet = swap::wallet_address().ok();
+
+    loop {
+        match ProviderBuilder::new().on_builtin(&rpc_url).await {
+            Ok(provider) => {
+                match provider.get_block_number().await {
+                    Ok(block) => {
+                        HEALTH.rpc_result(true);
+                        HEALTH.block_seen(block);
+                    }
+                    Err(e) => {
+                        HEALTH.rpc_result(false);
+                        METRICS.rpc_error("eth_blockNumber");
+                        eprintln!("Health probe: eth_blockNumber failed: {:?}", e);
+                    }
+                }
+                if let Some(address) = wallet {
+                    match provider.get_balance(address).await {
+                        Ok(balance) => HEALTH.wallet_balance(balance),
+                        Err(_) => METRICS.rpc_error("eth_getBalance"),
+                    }
+                }
+            }
+            Err(e) => {
+                HEALTH.rpc_result(false);
+                METRICS.rpc_error("connect");
+                eprintln!("Health probe: RPC connect failed: {:?}", e);
+            }
+        }
+        tokio::time::sleep(interval).await;
+    }
+}
+
+// Exits the process with a non-zero code once health has been bad for two consecutive checks,
+// so systemd's Restart= brings the bot back instead of leaving it running uselessly
+pub async fn run_watchdog(thresholds: Thresholds, interval: Duration) {
+    let mut consecutive_failures = 0;
+    loop {
+        tokio::time::sleep(interval).await;
+        let report = HEALTH.report(&thresholds);
+        if report.healthy {
+            consecutive_failures = 0;
+            continue;
+        }
+
+        consecutive_failures += 1;
+        let now = chrono::Local::now().format("%Y-%m-%d %H:%M:%S").to_string();
+        eprintln!("[{}] ⚠️ Watchdog: unhealthy ({}/2): {}", now, consecutive_failures, report.problems.join("; "));
+        if consecutive_failures >= 2 {
+            eprintln!("[{}] 💀 Watchdog: exiting so the service manager can restart the bot.", now);
+            std::process::exit(1);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn report() -> HealthReport {
+        HealthReport {
+            healthy: true,
+            ready: false,
+            discord_stage: "Connected".to_string(),
+            discord_connected: true,
+            discord_heartbeat_ms: Some(40),
+            secs_since_discord_event: Some(1.0),
+            secs_since_last_log: None,
+            active_monitors: 0,
+            last_block: Some(1),
+            secs_since_last_block: Some(3.0),
+            rpc_reachable: true,
+            secs_since_rpc_ok: Some(3.0),
+            wallet_balance_bnb: Some(1.0),
+            problems: Vec::new(),
+        }
+    }
+
+    fn thresholds() -> Thresholds {
+        Thresholds {
+            discord_stale: Duration::from_secs(120),
+            block_stale: Duration::from_secs(60),
+            rpc_stale: Duration::from_secs(60),
+            log_stale: Duration::from_secs(300),
+            startup_grace: Duration::from_secs(60),
+        }
+    }
+
+    #[test]
+    fn test_evaluate_staleness() {
+        let mut healthy = report();
+        evaluate(&mut healthy, &thresholds(), false, Some(500.0));
+        assert!(healthy.healthy && healthy.ready);
+
+        let mut stalled = report();
+        stalled.secs_since_last_block = Some(90.0);
+        stalled.discord_connected = false;
+        evaluate(&mut stalled, &thresholds(), false, Some(200.0));
+        assert!(!stalled.healthy && !stalled.ready);
+        assert_eq!(stalled.problems.len(), 2);
+
+        // Nothing observed yet is only a problem once the startup grace period is over
+        let mut fresh = report();
+        fresh.secs_since_rpc_ok = None;
+        evaluate(&mut fresh, &thresholds(), true, None);
+        assert!(fresh.healthy);
+        evaluate(&mut fresh, &thresholds(), false, None);
+        assert!(!fresh.healthy);
+
+        let mut quiet_monitor = report();
+        quiet_monitor.active_monitors = 1;
+        quiet_monitor.secs_since_last_log = Some(400.0);
+        evaluate(&mut quiet_monitor, &thresholds(), false, None);
+        assert_eq!(quiet_monitor.problems, vec!["Log monitor running but no logs received".to_string()]);
+    }
+}
@@ -0,0 +1,272 @@
+use alloy::{
+    primitives::{address, Address, Bytes, U256},
+    providers::{Provider, ProviderBuilder, RootProvider},
+    transports::BoxTransport,
+    sol,
+    sol_types::SolCall,
+};
+use serde_json::json;
+use std::env;
+use std::str::FromStr;
+use anyhow::{Result, anyhow};
+
+use crate::metrics::METRICS;
+use crate::swap::IERC20;
+
+sol!(
+    contract Multicall3 {
+        struct Call3Value {
+            address target;
+            bool allowFailure;
+            uint256 value;
+            bytes callData;
+        }
+
+        struct Result {
+            bool success;
+            bytes returnData;
+        }
+
+        function aggregate3Value(Call3Value[] calldata calls) external payable returns (Result[] memory returnData);
+    }
+);
+
+sol!(
+    #[sol(rpc)]
+    contract PancakeRouter {
+        function getAmountsOut(uint256 amountIn, address[] calldata path) external view returns (uint256[] memory amounts);
+
+        function swapExactETHForTokensSupportingFeeOnTransferTokens(uint256 amountOutMin, address[] calldata path, address to, uint256 deadline)
+            external
+            payable;
+
+        function swapExactTokensForTokensSupportingFeeOnTransferTokens(uint256 amountIn, uint256 amountOutMin, address[] calldata path, address to, uint256 deadline)
+            external;
+
+        function swapExactTokensForETHSupportingFeeOnTransferTokens(uint256 amountIn, uint256 amountOutMin, address[] calldata path, address to, uint256 deadline)
+            external;
+    }
+);
+
+sol!(
+    #[sol(rpc)]
+    contract PancakeFactory {
+        function getPair(address tokenA, address tokenB) external view returns (address pair);
+    }
+);
+
+// Deployed at the same address on every chain, BSC included
+const MULTICALL3: Address = address!("cA11bde05977b3631167028862bE2a173976CA11");
+pub const WBNB: Address = address!("bb4CdB9CBd36B01bD1cBaEBF2De08d9173bc095c");
+const PANCAKE_V2_ROUTER: Address = address!("10ED43C718714eb63d5aA57B78B54704E256024E");
+const PANCAKE_V2_FACTORY: Address = address!("cA143Ce32Fe78f1f7019d7d551a6402fC5350c73");
+// Any address works as the simulated sender; its balance is overridden for the call
+const SIM_SENDER: Address = address!("000000000000000000000000000000000000dEaD");
+
+pub fn router() -> Result<Address> {
+    match env::var("PANCAKE_V2_ROUTER") {
+        Ok(router) => Ok(Address::from_str(&router)?),
+        Err(_) => Ok(PANCAKE_V2_ROUTER),
+    }
+}
+
+pub fn factory() -> Result<Address> {
+    match env::var("PANCAKE_V2_FACTORY") {
+        Ok(factory) => Ok(Address::from_str(&factory)?),
+        Err(_) => Ok(PANCAKE_V2_FACTORY),
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct Thresholds {
+    pub max_buy_tax_pct: f64,
+    pub max_sell_tax_pct: f64,
+}
+
+impl Thresholds {
+    pub fn from_env() -> Self {
+        let pct = |key: &str| env::var(key).ok().and_then(|v| v.parse().ok()).unwrap_or(10.0);
+        Thresholds {
+            max_buy_tax_pct: pct("MAX_BUY_TAX_PCT"),
+            max_sell_tax_pct: pct("MAX_SELL_TAX_PCT"),
+        }
+    }
+}
+
+pub fn enabled() -> bool {
+    env::var("HONEYPOT_CHECK").map(|v| v != "false").unwrap_or(true)
+}
+
+// Result of simulating a buy and an immediate sell through the Pancake V2 router
+#[derive(Debug, Clone, PartialEq)]
+pub struct TaxReport {
+    pub buy_tax_pct: f64,
+    // None when the sell reverted, i.e. the token cannot be sold
+    pub sell_tax_pct: Option<f64>,
+}
+
+impl TaxReport {
+    // Tax is the share of the router's quote that never arrived
+    pub fn from_amounts(quoted_buy: U256, bought: U256, quoted_sell: U256, sold: Option<U256>) -> Self {
+        TaxReport {
+            buy_tax_pct: shortfall_pct(quoted_buy, bought),
+            sell_tax_pct: sold.map(|sold| shortfall_pct(quoted_sell, sold)),
+        }
+    }
+
+    // None when the trade may go ahead, otherwise why not
+    pub fn block_reason(&self, thresholds: &Thresholds) -> Option<String> {
+        match self.sell_tax_pct {
+            None => Some("sell reverted in simulation (honeypot)".to_string()),
+            Some(_) if self.buy_tax_pct > thresholds.max_buy_tax_pct => {
+                Some(format!("buy tax {:.1}% is above {:.1}%", self.buy_tax_pct, thresholds.max_buy_tax_pct))
+            }
+            Some(sell) if sell > thresholds.max_sell_tax_pct => {
+                Some(format!("sell tax {:.1}% is above {:.1}%", sell, thresholds.max_sell_tax_pct))
+            }
+            Some(_) => None,
+        }
+    }
+
+    pub fn summary(&self) -> String {
+        match self.sell_tax_pct {
+            Some(sell) => format!("buy tax {:.1}% | sell tax {:.1}%", self.buy_tax_pct, sell),
+            None => format!("buy tax {:.1}% | sell REVERTS", self.buy_tax_pct),
+        }
+    }
+}
+
+fn shortfall_pct(quoted: U256, received: U256) -> f64 {
+    let quoted = crate::swap::to_units(quoted);
+    if quoted <= 0.0 {
+        return 100.0;
+    }
+    ((1.0 - crate::swap::to_units(received) / quoted) * 100.0).clamp(0.0, 100.0)
+}
+
+fn call(target: Address, value: U256, data: Vec<u8>) -> Multicall3::Call3Value {
+    Multicall3::Call3Value { target, allowFailure: true, value, callData: Bytes::from(data) }
+}
+
+fn returned<C: SolCall>(results: &[Multicall3::Result], index: usize) -> Option<C::Return> {
+    let result = results.get(index).filter(|r| r.success)?;
+    C::abi_decode_returns(&result.returnData, true).ok()
+}
+
+// Runs the calls from Multicall3 in one eth_call, with the sender's balance topped up to cover the value
+async fn aggregate(provider: &RootProvider<BoxTransport>, calls: Vec<Multicall3::Call3Value>) -> Result<Vec<Multicall3::Result>> {
+    let value: U256 = calls.iter().map(|c| c.value).sum();
+    let data = Multicall3::aggregate3ValueCall { calls }.abi_encode();
+    let mut overrides = serde_json::Map::new();
+    overrides.insert(format!("{:?}", SIM_SENDER), json!({ "balance": format!("0x{:x}", value + U256::from(10).pow(U256::from(18))) }));
+    let params = json!([
+        {
+            "from": SIM_SENDER,
+            "to": MULTICALL3,
+            "value": format!("0x{:x}", value),
+            "data": format!("0x{}", hex::encode(data)),
+        },
+        "latest",
+        overrides
+    ]);
+    let raw: Bytes = provider.raw_request("eth_call".into(), params).await
+        .inspect_err(|_| METRICS.rpc_error("eth_call"))?;
+    Ok(Multicall3::aggregate3ValueCall::abi_decode_returns(&raw, true)?.returnData)
+}
+
+pub async fn is_listed(token: Address) -> Result<bool> {
+    let provider = ProviderBuilder::new().on_builtin(&env::var("BSC_RPC_URL")?).await?;
+    let factory = PancakeFactory::new(factory()?, provider);
+    Ok(!factory.getPair(token, WBNB).call().await?.pair.is_zero())
+}
+
+// Simulates buying `funds` of the token and selling everything straight back
+pub async fn simulate(token: Address, funds: U256) -> Result<TaxReport> {
+    let provider = ProviderBuilder::new().on_builtin(&env::var("BSC_RPC_URL")?).await?;
+    let router = router()?;
+    let buy_path = vec![WBNB, token];
+    let sell_path = vec![token, WBNB];
+    let balance = |token: Address| call(token, U256::ZERO, IERC20::balanceOfCall { owner: MULTICALL3 }.abi_encode());
+    let buy = call(router, funds, PancakeRouter::swapExactETHForTokensSupportingFeeOnTransferTokensCall {
+        amountOutMin: U256::ZERO,
+        path: buy_path.clone(),
+        to: MULTICALL3,
+        deadline: U256::MAX,
+    }.abi_encode());
+
+    // Pass 1: quote, buy, and see how many tokens actually arrived
+    let results = aggregate(&provider, vec![
+        call(router, U256::ZERO, PancakeRouter::getAmountsOutCall { amountIn: funds, path: buy_path }.abi_encode()),
+        balance(token),
+        buy.clone(),
+        balance(token),
+    ]).await?;
+    let quoted_buy = returned::<PancakeRouter::getAmountsOutCall>(&results, 0)
+        .and_then(|r| r.amounts.last().copied())
+        .ok_or_else(|| anyhow!("No Pancake V2 quote for {}", token))?;
+    if !results.get(2).is_some_and(|r| r.success) {
+        return Err(anyhow!("Buy of {} reverted in simulation", token));
+    }
+    let before = returned::<IERC20::balanceOfCall>(&results, 1).map(|r| r._0).unwrap_or_default();
+    let after = returned::<IERC20::balanceOfCall>(&results, 3).map(|r| r._0).unwrap_or_default();
+    let bought = after.saturating_sub(before);
+    if bought.is_zero() {
+        return Ok(TaxReport::from_amounts(quoted_buy, bought, U256::ZERO, None));
+    }
+
+    // Pass 2: repeat the buy, then sell exactly what arrived back into WBNB
+    let results = aggregate(&provider, vec![
+        buy,
+        call(token, U256::ZERO, IERC20::approveCall { spender: router, amount: U256::MAX }.abi_encode()),
+        call(router, U256::ZERO, PancakeRouter::getAmountsOutCall { amountIn: bought, path: sell_path.clone() }.abi_encode()),
+        balance(WBNB),
+        call(router, U256::ZERO, PancakeRouter::swapExactTokensForTokensSupportingFeeOnTransferTokensCall {
+            amountIn: bought,
+            amountOutMin: U256::ZERO,
+            path: sell_path,
+            to: MULTICALL3,
+            deadline: U256::MAX,
+        }.abi_encode()),
+        balance(WBNB),
+    ]).await?;
+    let quoted_sell = returned::<PancakeRouter::getAmountsOutCall>(&results, 2)
+        .and_then(|r| r.amounts.last().copied())
+        .unwrap_or_default();
+    let sold = match (results.get(4).is_some_and(|r| r.success), returned::<IERC20::balanceOfCall>(&results, 3), returned::<IERC20::balanceOfCall>(&results, 5)) {
+        (true, Some(before), Some(after)) => Some(after._0.saturating_sub(before._0)),
+        _ => None,
+    };
+    Ok(TaxReport::from_amounts(quoted_buy, bought, quoted_sell, sold))
+}
+
+// Pre-trade gate for Pancake-listed tokens: Ok(None) to go ahead, Ok(Some(reason)) to block
+pub async fn check(token: Address, funds: U256) -> Result<Option<String>> {
+    if !enabled() || !is_listed(token).await? {
+        return Ok(None);
+    }
+    let report = simulate(token, funds).await?;
+    let now = chrono::Local::now().format("%Y-%m-%d %H:%M:%S").to_string();
+    println!("[{}] 🍯 Honeypot check for {:?}: {}", now, token, report.summary());
+    Ok(report.block_reason(&Thresholds::from_env()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tax_report() {
+        let thresholds = Thresholds { max_buy_tax_pct: 10.0, max_sell_tax_pct: 10.0 };
+        let units = |n: u64| U256::from(n) * U256::from(10).pow(U256::from(16));
+
+        let clean = TaxReport::from_amounts(units(100), units(99), units(50), Some(units(49)));
+        assert!((clean.buy_tax_pct - 1.0).abs() < 1e-9);
+        assert_eq!(clean.block_reason(&thresholds), None);
+
+        let taxed = TaxReport::from_amounts(units(100), units(95), units(50), Some(units(30)));
+        assert!(taxed.block_reason(&thresholds).unwrap().starts_with("sell tax 40.0%"));
+
+        let honeypot = TaxReport::from_amounts(units(100), units(100), units(50), None);
+        assert!(honeypot.block_reason(&thresholds).unwrap().contains("honeypot"));
+    }
+}
@@ -0,0 +1,187 @@
+use alloy::{
+    primitives::{b256, keccak256, Address, B256},
+    providers::Provider,
+    transports::Transport,
+};
+use std::collections::HashSet;
+use std::env;
+use anyhow::Result;
+
+use crate::metrics::METRICS;
+
+// Functions a four.meme token never has: the supply is fixed at creation and transfers are unrestricted
+const MINT_SIGNATURES: [&str; 3] = ["mint(address,uint256)", "mint(uint256)", "mintTo(address,uint256)"];
+const BLACKLIST_SIGNATURES: [&str; 6] = [
+    "blacklist(address)",
+    "addToBlacklist(address)",
+    "addBlackList(address)",
+    "setBlacklist(address,bool)",
+    "setBots(address[],bool)",
+    "isBlacklisted(address)",
+];
+const UPGRADE_SIGNATURES: [&str; 2] = ["upgradeTo(address)", "upgradeToAndCall(address,bytes)"];
+
+// bytes32(uint256(keccak256("eip1967.proxy.implementation")) - 1)
+const EIP1967_IMPLEMENTATION_SLOT: B256 = b256!("360894a13ba1a3210667c828492db98dca3e2076cc3735a920a3ca505d382bbc");
+const EIP1167_PREFIX: [u8; 10] = [0x36, 0x3d, 0x3d, 0x37, 0x3d, 0x3d, 0x3d, 0x36, 0x3d, 0x73];
+
+const PUSH1: u8 = 0x60;
+const PUSH4: u8 = 0x63;
+const PUSH32: u8 = 0x7f;
+const DELEGATECALL: u8 = 0xf4;
+
+// Runtime code hashes of the official four.meme token template(s)
+pub fn template_hashes() -> Vec<B256> {
+    env::var("TOKEN_TEMPLATE_HASHES")
+        .unwrap_or_default()
+        .split(',')
+        .filter_map(|h| h.trim().parse().ok())
+        .collect()
+}
+
+// Also refuse tokens that merely differ from the template, not just ones with suspicious selectors
+fn strict() -> bool {
+    env::var("TOKEN_TEMPLATE_STRICT").is_ok_and(|v| v == "true")
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Inspection {
+    pub code_hash: B256,
+    // None when no template hashes are configured
+    pub template_match: Option<bool>,
+    pub flags: Vec<String>,
+}
+
+impl Inspection {
+    // None when the token may be bought, otherwise why not
+    pub fn block_reason(&self, strict: bool) -> Option<String> {
+        if !self.flags.is_empty() {
+            return Some(format!("non-standard contract: {}", self.flags.join(", ")));
+        }
+        if strict && self.template_match == Some(false) {
+            return Some(format!("bytecode hash {:?} is not a known four.meme template", self.code_hash));
+        }
+        None
+    }
+}
+
+fn selector(signature: &str) -> [u8; 4] {
+    keccak256(signature.as_bytes())[..4].try_into().unwrap()
+}
+
+pub fn inspect_code(code: &[u8], templates: &[B256]) -> Inspection {
+    let code_hash = keccak256(code);
+    let template_match = (!templates.is_empty()).then(|| templates.contains(&code_hash));
+    if template_match == Some(true) {
+        return Inspection { code_hash, template_match, flags: Vec::new() };
+    }
+
+    let mut flags = Vec::new();
+    if code.is_empty() {
+        flags.push("no contract code".to_string());
+        return Inspection { code_hash, template_match, flags };
+    }
+    if code.starts_with(&EIP1167_PREFIX) {
+        flags.push("minimal proxy (EIP-1167)".to_string());
+    }
+
+    // Walk the opcodes so push data is never mistaken for an instruction
+    let mut selectors = HashSet::new();
+    let (mut delegatecall, mut eip1967) = (false, false);
+    let mut pc = 0;
+    while pc < code.len() {
+        let op = code[pc];
+        if (PUSH1..=PUSH32).contains(&op) {
+            let size = (op - PUSH1 + 1) as usize;
+            let data = &code[(pc + 1).min(code.len())..(pc + 1 + size).min(code.len())];
+            if op == PUSH4 && data.len() == 4 {
+                selectors.insert([data[0], data[1], data[2], data[3]]);
+            } else if op == PUSH32 && data == EIP1967_IMPLEMENTATION_SLOT.as_slice() {
+                eip1967 = true;
+            }
+            pc += 1 + size;
+            continue;
+        }
+        delegatecall |= op == DELEGATECALL;
+        pc += 1;
+    }
+
+    let found = |signatures: &[&str]| -> Vec<String> {
+        signatures.iter().filter(|s| selectors.contains(&selector(s))).map(|s| s.to_string()).collect()
+    };
+    let mint = found(&MINT_SIGNATURES);
+    if !mint.is_empty() {
+        flags.push(format!("owner mint ({})", mint.join(", ")));
+    }
+    let blacklist = found(&BLACKLIST_SIGNATURES);
+    if !blacklist.is_empty() {
+        flags.push(format!("blacklist ({})", blacklist.join(", ")));
+    }
+    let upgrade = found(&UPGRADE_SIGNATURES);
+    if eip1967 || !upgrade.is_empty() {
+        flags.push("upgradeable proxy".to_string());
+    } else if delegatecall {
+        flags.push("delegatecall".to_string());
+    }
+
+    Inspection { code_hash, template_match, flags }
+}
+
+// Fetches the token's runtime code and returns why it must not be bought, if anything
+pub async fn check<P, T>(provider: &P, token: Address) -> Result<Option<String>>
+where
+    P: Provider<T>,
+    T: Transport + Clone,
+{
+    let code = provider.get_code_at(token).await
+        .inspect_err(|_| METRICS.rpc_error("eth_getCode"))?;
+    let inspection = inspect_code(&code, &template_hashes());
+    if inspection.template_match == Some(false) {
+        let now = chrono::Local::now().format("%Y-%m-%d %H:%M:%S").to_string();
+        println!("[{}] 🔍 {:?} bytecode hash {:?} is not a known template", now, token, inspection.code_hash);
+    }
+    Ok(inspection.block_reason(strict()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn dispatch(signatures: &[&str]) -> Vec<u8> {
+        let mut code = vec![0x60, 0x80, 0x60, 0x40, 0x52];
+        for signature in signatures {
+            code.extend_from_slice(&[0x80, PUSH4]);
+            code.extend_from_slice(&selector(signature));
+            code.extend_from_slice(&[0x14, 0x61, 0x00, 0x10, 0x57]);
+        }
+        code
+    }
+
+    #[test]
+    fn test_inspect_code() {
+        let clean = dispatch(&["transfer(address,uint256)", "balanceOf(address)"]);
+        let inspection = inspect_code(&clean, &[]);
+        assert_eq!(inspection.template_match, None);
+        assert!(inspection.flags.is_empty());
+        assert_eq!(inspect_code(&clean, &[keccak256(&clean)]).template_match, Some(true));
+        assert!(inspect_code(&clean, &[B256::ZERO]).block_reason(true).is_some());
+
+        let rugpull = dispatch(&["transfer(address,uint256)", "mint(address,uint256)", "setBlacklist(address,bool)"]);
+        let inspection = inspect_code(&rugpull, &[]);
+        assert_eq!(inspection.flags, vec!["owner mint (mint(address,uint256))", "blacklist (setBlacklist(address,bool))"]);
+
+        // A selector hidden inside other push data is not a function
+        let mut hidden = vec![0x6b];
+        hidden.extend_from_slice(&[0; 4]);
+        hidden.push(PUSH4);
+        hidden.extend_from_slice(&selector("mint(uint256)"));
+        hidden.extend_from_slice(&[0; 3]);
+        assert!(inspect_code(&hidden, &[]).flags.is_empty());
+
+        let mut clone = EIP1167_PREFIX.to_vec();
+        clone.extend_from_slice(&[0x11; 20]);
+        clone.extend_from_slice(&[0x5a, DELEGATECALL]);
+        assert!(inspect_code(&clone, &[]).flags.contains(&"minimal proxy (EIP-1167)".to_string()));
+        assert_eq!(inspect_code(&[], &[]).flags, vec!["no contract code"]);
+    }
+}
@@ -0,0 +1,173 @@
+use alloy::primitives::{Address, B256};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::env;
+use std::fs::OpenOptions;
+use std::io::{BufRead, BufReader, Write};
+use anyhow::Result;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Side {
+    Buy,
+    Sell,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Status {
+    Sent,
+    Confirmed,
+    Reverted,
+}
+
+// One line of the append-only trade journal. Later lines for the same tx_hash
+// (e.g. once the receipt lands) supersede earlier ones.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JournalEntry {
+    pub timestamp: i64,
+    pub side: Side,
+    pub token: String,
+    pub tx_hash: String,
+    pub status: Status,
+    // BNB spent for buys, BNB received for sells
+    pub bnb: Option<f64>,
+    pub tokens: Option<f64>,
+    // Sending wallet, for setups with more than one
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub wallet: Option<String>,
+}
+
+impl JournalEntry {
+    pub fn new(side: Side, token: &str, tx_hash: B256, bnb: Option<f64>, tokens: Option<f64>) -> Self {
+        Self {
+            timestamp: chrono::Utc::now().timestamp(),
+            side,
+            token: token.to_lowercase(),
+            tx_hash: format!("{:?}", tx_hash),
+            status: Status::Sent,
+            bnb,
+            tokens,
+            wallet: None,
+        }
+    }
+
+    pub fn with_wallet(self, wallet: Address) -> Self {
+        Self { wallet: Some(format!("{:?}", wallet)), ..self }
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Position {
+    pub token: String,
+    pub buys: usize,
+    pub sells: usize,
+    pub bnb_spent: f64,
+    pub bnb_received: f64,
+    pub tokens_bought: f64,
+    pub tokens_sold: f64,
+    // Part of `tokens_sold` whose BNB proceeds are not known (pending or unparsed sells)
+    pub tokens_sold_unpriced: f64,
+}
+
+pub fn path() -> String {
+    env::var("JOURNAL_PATH").unwrap_or_else(|_| "trades.jsonl".to_string())
+}
+
+pub fn append(path: &str, entry: &JournalEntry) -> Result<()> {
+    let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+    writeln!(file, "{}", serde_json::to_string(entry)?)?;
+    Ok(())
+}
+
+// Journal failures must never block a trade, so they are only logged
+pub fn record(entry: JournalEntry) {
+    if let Err(e) = append(&path(), &entry) {
+        eprintln!("Failed to write trade journal: {:?}", e);
+    }
+}
+
+// Reads the journal, keeping only the latest line per transaction
+pub fn load(path: &str) -> Result<Vec<JournalEntry>> {
+    let file = match std::fs::File::open(path) {
+        Ok(f) => f,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(e.into()),
+    };
+
+    let mut order = Vec::new();
+    let mut latest: BTreeMap<String, JournalEntry> = BTreeMap::new();
+    for line in BufReader::new(file).lines() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let entry: JournalEntry = serde_json::from_str(&line)?;
+        if !latest.contains_key(&entry.tx_hash) {
+            order.push(entry.tx_hash.clone());
+        }
+        latest.insert(entry.tx_hash.clone(), entry);
+    }
+
+    Ok(order.into_iter().filter_map(|hash| latest.remove(&hash)).collect())
+}
+
+pub fn positions(entries: &[JournalEntry]) -> Vec<Position> {
+    let mut by_token: BTreeMap<String, Position> = BTreeMap::new();
+    for entry in entries.iter().filter(|e| e.status != Status::Reverted) {
+        let position = by_token.entry(entry.token.clone()).or_insert_with(|| Position {
+            token: entry.token.clone(),
+            ..Default::default()
+        });
+        match entry.side {
+            Side::Buy => {
+                position.buys += 1;
+                position.bnb_spent += entry.bnb.unwrap_or_default();
+                position.tokens_bought += entry.tokens.unwrap_or_default();
+            }
+            Side::Sell => {
+                position.sells += 1;
+                let tokens = entry.tokens.unwrap_or_default();
+                position.tokens_sold += tokens;
+                match entry.bnb {
+                    Some(bnb) => position.bnb_received += bnb,
+                    None => position.tokens_sold_unpriced += tokens,
+                }
+            }
+        }
+    }
+    by_token.into_values().collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_load_and_positions() {
+        let path = env::temp_dir().join(format!("journal-test-{}.jsonl", std::process::id()));
+        let path = path.to_str().unwrap();
+        let _ = std::fs::remove_file(path);
+
+        let token = "0xAbC0000000000000000000000000000000004444";
+        let buy = JournalEntry::new(Side::Buy, token, B256::repeat_byte(1), Some(0.1), None);
+        append(path, &buy).unwrap();
+        append(path, &JournalEntry { status: Status::Confirmed, tokens: Some(1000.0), ..buy.clone() }).unwrap();
+        let reverted = JournalEntry::new(Side::Buy, token, B256::repeat_byte(2), Some(0.5), None);
+        append(path, &JournalEntry { status: Status::Reverted, ..reverted }).unwrap();
+        append(path, &JournalEntry::new(Side::Sell, token, B256::repeat_byte(3), Some(0.2), Some(400.0))).unwrap();
+
+        let entries = load(path).unwrap();
+        assert_eq!(entries.len(), 3);
+        assert_eq!(entries[0].status, Status::Confirmed);
+
+        let positions = positions(&entries);
+        assert_eq!(positions.len(), 1);
+        assert_eq!(positions[0].token, token.to_lowercase());
+        assert_eq!((positions[0].buys, positions[0].sells), (1, 1));
+        assert_eq!(positions[0].bnb_spent, 0.1);
+        assert_eq!(positions[0].tokens_bought - positions[0].tokens_sold, 600.0);
+
+        std::fs::remove_file(path).unwrap();
+    }
+}
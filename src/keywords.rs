@@ -0,0 +1,186 @@
+use regex::Regex;
+use serde::Deserialize;
+use anyhow::{Result, anyhow};
+
+use crate::decoder::contains_chinese;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum KeywordMode {
+    // Whole word; a trailing * also accepts longer words ("publish*" -> "publishing")
+    #[default]
+    Word,
+    // Consecutive words, ignoring punctuation and spacing between them
+    Phrase,
+    // Case-insensitive regular expression
+    Regex,
+    // Plain substring, for scripts without spaces between words
+    Cjk,
+}
+
+// Keywords are written as plain strings or {"pattern": ..., "mode": ...}
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum KeywordSpec {
+    Plain(String),
+    Full {
+        pattern: String,
+        #[serde(default)]
+        mode: Option<KeywordMode>,
+    },
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(try_from = "KeywordSpec")]
+pub struct Keyword {
+    pub pattern: String,
+    pub mode: KeywordMode,
+    regex: Option<Regex>,
+}
+
+impl TryFrom<KeywordSpec> for Keyword {
+    type Error = anyhow::Error;
+
+    fn try_from(spec: KeywordSpec) -> Result<Self> {
+        match spec {
+            KeywordSpec::Plain(pattern) => Ok(Keyword::from(pattern.as_str())),
+            KeywordSpec::Full { pattern, mode } => match mode {
+                Some(mode) => Keyword::new(&pattern, mode),
+                None => Ok(Keyword::from(pattern.as_str())),
+            },
+        }
+    }
+}
+
+// Picks the mode from the pattern: CJK text as a substring, several words as a phrase
+impl From<&str> for Keyword {
+    fn from(pattern: &str) -> Self {
+        let mode = if contains_chinese(pattern) {
+            KeywordMode::Cjk
+        } else if pattern.split_whitespace().count() > 1 {
+            KeywordMode::Phrase
+        } else {
+            KeywordMode::Word
+        };
+        Keyword { pattern: pattern.to_string(), mode, regex: None }
+    }
+}
+
+impl PartialEq for Keyword {
+    fn eq(&self, other: &Self) -> bool {
+        self.pattern == other.pattern && self.mode == other.mode
+    }
+}
+
+impl Keyword {
+    pub fn new(pattern: &str, mode: KeywordMode) -> Result<Self> {
+        let regex = match mode {
+            KeywordMode::Regex => Some(
+                Regex::new(&format!("(?i){}", pattern))
+                    .map_err(|e| anyhow!("Invalid keyword regex '{}': {}", pattern, e))?,
+            ),
+            _ => None,
+        };
+        Ok(Keyword { pattern: pattern.to_string(), mode, regex })
+    }
+
+    pub fn matches(&self, text: &MatchText) -> bool {
+        let pattern = self.pattern.to_lowercase();
+        match self.mode {
+            KeywordMode::Word => match pattern.strip_suffix('*') {
+                Some(prefix) => text.words.iter().any(|w| w.starts_with(prefix)),
+                None => text.words.contains(&pattern),
+            },
+            KeywordMode::Phrase => {
+                let phrase = words(&pattern);
+                !phrase.is_empty() && text.words.windows(phrase.len()).any(|window| window == phrase.as_slice())
+            }
+            KeywordMode::Regex => self.regex.as_ref().is_some_and(|r| r.is_match(&text.folded)),
+            KeywordMode::Cjk => text.folded.contains(&pattern),
+        }
+    }
+}
+
+// Tweet text prepared once for every keyword: URLs removed, case folded, split into words
+pub struct MatchText {
+    pub folded: String,
+    pub words: Vec<String>,
+}
+
+impl MatchText {
+    pub fn new(text: &str) -> Self {
+        let folded = text
+            .split_whitespace()
+            .filter(|word| !is_url(word))
+            .collect::<Vec<_>>()
+            .join(" ")
+            .to_lowercase();
+        let words = words(&folded);
+        MatchText { folded, words }
+    }
+}
+
+fn is_url(word: &str) -> bool {
+    let word = word.trim_start_matches(['(', '<', '"', '\'']);
+    word.contains("://") || word.starts_with("www.") || word.starts_with("t.co/")
+}
+
+fn words(text: &str) -> Vec<String> {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|w| !w.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+// First keyword found in the text, in rule-set order
+pub fn find<'a>(keywords: &'a [Keyword], text: &str) -> Option<&'a Keyword> {
+    let text = MatchText::new(text);
+    keywords.iter().find(|k| k.matches(&text))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Deserialize)]
+    struct Case {
+        // "synthetic" for a tweet written to exercise a pattern, "archived:<tweet id>" for a real one
+        source: String,
+        text: String,
+        // Pattern of the keyword expected to match first, or null for no trigger
+        expect: Option<String>,
+    }
+
+    #[test]
+    fn test_cz_tweet_corpus() {
+        let keywords: Vec<Keyword> = serde_json::from_str(r#"[
+            "book*",
+            "publish*",
+            "release*",
+            "freedom of money",
+            "新书",
+            "发布",
+            {"pattern": "pre-?orders?", "mode": "regex"}
+        ]"#).unwrap();
+
+        for line in include_str!("../testdata/cz_tweets.jsonl").lines().filter(|l| !l.trim().is_empty()) {
+            let case: Case = serde_json::from_str(line).unwrap();
+            assert!(case.source == "synthetic" || case.source.strip_prefix("archived:").is_some_and(|id| id.parse::<u64>().is_ok()), "unlabelled tweet: {}", case.text);
+            let found = find(&keywords, &case.text).map(|k| k.pattern.clone());
+            assert_eq!(found, case.expect, "tweet: {}", case.text);
+        }
+    }
+
+    #[test]
+    fn test_modes() {
+        assert_eq!(Keyword::from("book").mode, KeywordMode::Word);
+        assert_eq!(Keyword::from("freedom of money").mode, KeywordMode::Phrase);
+        assert_eq!(Keyword::from("新书").mode, KeywordMode::Cjk);
+        assert!(serde_json::from_str::<Keyword>(r#"{"pattern": "(unclosed", "mode": "regex"}"#).is_err());
+
+        let text = MatchText::new("Facebook RELEASED https://release.example.com/book");
+        assert!(!Keyword::from("book").matches(&text));
+        assert!(!Keyword::from("release").matches(&text));
+        assert!(Keyword::from("release*").matches(&text));
+    }
+}
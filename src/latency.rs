@@ -0,0 +1,170 @@
+use std::collections::{HashMap, VecDeque};
+use std::time::Instant;
+
+// Number of recent trades kept per stage for the rolling percentiles
+const WINDOW: usize = 256;
+
+pub const STAGES: [&str; 7] = [
+    "relay",
+    "trigger_to_log",
+    "block_to_log",
+    "decode",
+    "sign",
+    "broadcast",
+    "log_to_broadcast",
+];
+
+// Timing capture for a single trade, from Discord receipt to eth_sendRawTransaction
+#[derive(Debug, Clone)]
+pub struct TradeTimings {
+    pub trigger_at: Option<Instant>,
+    pub relay_delay_ms: Option<i64>,
+    pub log_received_at: Instant,
+    pub log_received_unix_ms: i64,
+    pub block_number: Option<u64>,
+    pub block_timestamp: Option<u64>,
+    pub decoded_at: Option<Instant>,
+    pub swap_started_at: Option<Instant>,
+    pub signed_at: Option<Instant>,
+    pub broadcast_at: Option<Instant>,
+}
+
+impl TradeTimings {
+    pub fn new(trigger_at: Option<Instant>, relay_delay_ms: Option<i64>) -> Self {
+        Self {
+            trigger_at,
+            relay_delay_ms,
+            log_received_at: Instant::now(),
+            log_received_unix_ms: chrono::Utc::now().timestamp_millis(),
+            block_number: None,
+            block_timestamp: None,
+            decoded_at: None,
+            swap_started_at: None,
+            signed_at: None,
+            broadcast_at: None,
+        }
+    }
+
+    // Returns (stage, milliseconds) for every stage that has both ends captured
+    pub fn breakdown(&self) -> Vec<(&'static str, f64)> {
+        let ms = |from: Option<Instant>, to: Option<Instant>| match (from, to) {
+            (Some(a), Some(b)) if b >= a => Some(b.duration_since(a).as_secs_f64() * 1000.0),
+            _ => None,
+        };
+        let log_at = Some(self.log_received_at);
+
+        let mut out = Vec::new();
+        if let Some(d) = self.relay_delay_ms {
+            out.push(("relay", d as f64));
+        }
+        if let Some(d) = ms(self.trigger_at, log_at) {
+            out.push(("trigger_to_log", d));
+        }
+        if let Some(ts) = self.block_timestamp {
+            out.push(("block_to_log", (self.log_received_unix_ms - ts as i64 * 1000) as f64));
+        }
+        if let Some(d) = ms(log_at, self.decoded_at) {
+            out.push(("decode", d));
+        }
+        if let Some(d) = ms(self.swap_started_at.or(self.decoded_at), self.signed_at) {
+            out.push(("sign", d));
+        }
+        if let Some(d) = ms(self.signed_at, self.broadcast_at) {
+            out.push(("broadcast", d));
+        }
+        if let Some(d) = ms(log_at, self.broadcast_at) {
+            out.push(("log_to_broadcast", d));
+        }
+        out
+    }
+
+    pub fn summary(&self) -> String {
+        self.breakdown()
+            .iter()
+            .map(|(stage, ms)| format!("{}={:.1}ms", stage, ms))
+            .collect::<Vec<_>>()
+            .join(" | ")
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct LatencyTracker {
+    samples: HashMap<&'static str, VecDeque<f64>>,
+}
+
+impl LatencyTracker {
+    pub fn record(&mut self, timings: &TradeTimings) {
+        for (stage, ms) in timings.breakdown() {
+            let buf = self.samples.entry(stage).or_default();
+            if buf.len() == WINDOW {
+                buf.pop_front();
+            }
+            buf.push_back(ms);
+        }
+    }
+
+    pub fn percentile(&self, stage: &str, p: f64) -> Option<f64> {
+        let buf = self.samples.get(stage)?;
+        if buf.is_empty() {
+            return None;
+        }
+        let mut sorted: Vec<f64> = buf.iter().copied().collect();
+        sorted.sort_by(|a, b| a.total_cmp(b));
+        let rank = ((p / 100.0) * (sorted.len() - 1) as f64).round() as usize;
+        Some(sorted[rank.min(sorted.len() - 1)])
+    }
+
+    pub fn report(&self) -> String {
+        let mut lines = Vec::new();
+        for stage in STAGES {
+            let n = self.samples.get(stage).map_or(0, |b| b.len());
+            if n == 0 {
+                continue;
+            }
+            lines.push(format!(
+                "{:<17} n={:<4} p50={:>8.1}ms p90={:>8.1}ms p99={:>8.1}ms",
+                stage,
+                n,
+                self.percentile(stage, 50.0).unwrap_or_default(),
+                self.percentile(stage, 90.0).unwrap_or_default(),
+                self.percentile(stage, 99.0).unwrap_or_default(),
+            ));
+        }
+        lines.join("\n")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn test_breakdown_and_percentiles() {
+        let base = Instant::now();
+        let mut t = TradeTimings::new(Some(base), Some(1200));
+        t.log_received_at = base + Duration::from_millis(100);
+        t.decoded_at = Some(base + Duration::from_millis(101));
+        t.signed_at = Some(base + Duration::from_millis(131));
+        t.broadcast_at = Some(base + Duration::from_millis(181));
+        t.log_received_unix_ms = 1_700_000_001_500;
+        t.block_timestamp = Some(1_700_000_001);
+
+        let stages: HashMap<_, _> = t.breakdown().into_iter().collect();
+        assert_eq!(stages["relay"], 1200.0);
+        assert_eq!(stages["block_to_log"], 500.0);
+        assert!((stages["trigger_to_log"] - 100.0).abs() < 0.01);
+        assert!((stages["sign"] - 30.0).abs() < 0.01);
+        assert!((stages["log_to_broadcast"] - 81.0).abs() < 0.01);
+
+        let mut tracker = LatencyTracker::default();
+        for relay in 1..=100 {
+            let mut sample = TradeTimings::new(None, Some(relay));
+            sample.log_received_at = base;
+            tracker.record(&sample);
+        }
+        assert_eq!(tracker.percentile("relay", 50.0), Some(51.0));
+        assert_eq!(tracker.percentile("relay", 99.0), Some(99.0));
+        assert_eq!(tracker.percentile("sign", 50.0), None);
+    }
+}
@@ -1,10 +1,131 @@
 pub mod swap;
 pub mod decoder;
 pub mod monitor;
+pub mod latency;
+pub mod metrics;
+pub mod server;
+pub mod health;
+pub mod journal;
+pub mod commands;
+pub mod confirm;
+pub mod notify;
+pub mod rules;
+pub mod tweet;
+pub mod keywords;
+pub mod targets;
+pub mod watchlist;
+pub mod reputation;
+pub mod honeypot;
+pub mod inspect;
+pub mod curve;
+pub mod migration;
+pub mod portfolio;
+pub mod wallets;
+pub mod signer;
+pub mod triggers;
+pub mod discord;
+pub mod feed;
+pub mod telegram;
+#[cfg(test)]
+mod testutil;
 
-use std::time::Instant;
+use std::collections::{HashMap, HashSet};
+use std::env;
+use std::time::{Duration, Instant};
+
+// How long a tweet trigger keeps the buy window open
+pub const BUY_WINDOW: Duration = Duration::from_secs(5);
 
 pub struct SharedState {
     pub last_cz_tweet_time: Option<Instant>,
-    pub channel_id: u64,
+    pub last_trigger_relay_ms: Option<i64>,
+    // Manually opened buy window from /arm
+    pub armed_until: Option<Instant>,
+    // Post candidates with Buy/Skip buttons instead of buying immediately
+    pub confirm_mode: bool,
+    pub armed_confirm: bool,
+    pub pending_confirmations: HashMap<String, confirm::Pending>,
+    // Token names pulled from the last trigger tweet; empty falls back to any Chinese name
+    pub targets: Vec<String>,
+    // Pre-announced tokens bought on creation, outside any window
+    pub watchlist: watchlist::Watchlist,
+    pub watchlist_monitor: bool,
+    // Creator history and allow/deny lists consulted before every buy
+    pub reputation: reputation::Reputation,
+    // Tokens a buy has been sent for, so overlapping monitors don't buy twice
+    pub bought: HashSet<String>,
+    // Tokens seen graduating to PancakeSwap, so each migration is announced once
+    pub migrated: HashSet<String>,
+    pub buy_amount_bnb: f64,
+    // Watched channels, their rule sets and the notification channel
+    pub triggers: rules::TriggerConfig,
+    // Discord user IDs allowed to run control commands
+    pub admin_ids: HashSet<u64>,
+    pub latency: latency::LatencyTracker,
+}
+
+impl SharedState {
+    pub fn new(triggers: rules::TriggerConfig) -> Self {
+        Self {
+            last_cz_tweet_time: None,
+            last_trigger_relay_ms: None,
+            armed_until: None,
+            confirm_mode: env::var("SNIPER_MODE").is_ok_and(|m| m.eq_ignore_ascii_case("confirm")),
+            armed_confirm: false,
+            pending_confirmations: HashMap::new(),
+            targets: Vec::new(),
+            watchlist: watchlist::Watchlist::load(&watchlist::path()).unwrap_or_else(|e| {
+                eprintln!("Failed to load watchlist: {:?}", e);
+                watchlist::Watchlist::default()
+            }),
+            watchlist_monitor: false,
+            reputation: reputation::Reputation::load(&reputation::path()).unwrap_or_else(|e| {
+                eprintln!("Failed to load creator reputation: {:?}", e);
+                reputation::Reputation::default()
+            }),
+            bought: HashSet::new(),
+            migrated: HashSet::new(),
+            buy_amount_bnb: env::var("BUY_AMOUNT_BNB").ok().and_then(|v| v.parse().ok()).unwrap_or(0.0),
+            triggers,
+            admin_ids: env::var("DISCORD_ADMIN_IDS")
+                .unwrap_or_default()
+                .split(',')
+                .filter_map(|id| id.trim().parse().ok())
+                .collect(),
+            latency: latency::LatencyTracker::default(),
+        }
+    }
+
+    pub fn window_open(&self, now: Instant) -> bool {
+        let tweet = self.last_cz_tweet_time.is_some_and(|t| now.duration_since(t) < BUY_WINDOW);
+        let armed = self.armed_until.is_some_and(|until| now < until);
+        tweet || armed
+    }
+
+    // True the first time a token is claimed for buying
+    pub fn claim_buy(&mut self, token: &str) -> bool {
+        self.bought.insert(token.to_lowercase())
+    }
+
+    // Listing buys are claimed apart from the launch buy, which usually came first
+    pub fn claim_listing_buy(&mut self, token: &str) -> bool {
+        self.bought.insert(format!("listing:{}", token.to_lowercase()))
+    }
+
+    // Lets a failed manual buy be retried
+    pub fn release_buy(&mut self, token: &str, listing: bool) {
+        let token = token.to_lowercase();
+        self.bought.remove(&if listing { format!("listing:{}", token) } else { token });
+    }
+
+    // Candidates nobody acted on within CONFIRM_TTL_SECS can no longer be bought
+    pub fn expire_confirmations(&mut self, now: Instant) {
+        let ttl = confirm::ttl();
+        self.pending_confirmations.retain(|_, pending| !pending.expired(now, ttl));
+    }
+
+    pub fn confirm_required(&self, now: Instant) -> bool {
+        let armed = self.armed_until.is_some_and(|until| now < until);
+        self.confirm_mode || (self.armed_confirm && armed)
+    }
 }
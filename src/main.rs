@@ -1,155 +1,63 @@
-use serenity::{
-    async_trait,
-    model::{channel::Message, gateway::Ready},
-    prelude::*,
-};
+use serenity::http::Http;
 use std::env;
 use std::sync::Arc;
-use tokio::sync::Mutex;
-use std::time::{Duration, Instant};
+use tokio::sync::{mpsc, Mutex};
+use std::time::Duration;
 use anyhow::Result;
 
-use bsc_discord_sniper::{SharedState, monitor};
+use bsc_discord_sniper::{SharedState, migration, monitor, reputation, server, wallets, feed, telegram, discord::DiscordSource, rules::TriggerConfig, triggers::{self, TriggerSource}, health::{self, HEALTH}};
 
-struct Handler {
-    state: Arc<Mutex<SharedState>>,
-}
-
-#[async_trait]
-impl EventHandler for Handler {
-    async fn message(&self, ctx: Context, msg: Message) {
-        let mut state = self.state.lock().await;
-        let now = chrono::Local::now().format("%Y-%m-%d %H:%M:%S").to_string();
-        
-        // Debug: Log every message for troubleshooting with timestamp
-        println!("[{}] [Debug] Message Context -> ChannelID: {}, Content: '{}', Embeds: {}", 
-            now, msg.channel_id, msg.content, msg.embeds.len());
-
-        if msg.channel_id.get() != state.channel_id {
-            return;
-        }
-
-        // Check if message content OR any embed contains the triggers
-        let triggers = ["New Tweet from @cz_binance", "New Tweet from @Scratch_XOX"];
-        let keywords = ["book", "publish", "release"];
-        
-        let mut account_matched = triggers.iter().any(|&t| msg.content == t);
-        let mut content_to_search = msg.content.to_lowercase();
-        
-        if !account_matched {
-            for embed in &msg.embeds {
-                if let Some(desc) = &embed.description {
-                    if triggers.iter().any(|&t| desc.contains(t)) {
-                        account_matched = true;
-                        content_to_search.push_str(&desc.to_lowercase());
-                    }
-                }
-                if let Some(title) = &embed.title {
-                    if triggers.iter().any(|&t| title.contains(t)) {
-                        account_matched = true;
-                        content_to_search.push_str(&title.to_lowercase());
-                    }
-                }
-            }
-        }
-
-        // Only trigger if account matched AND one of the keywords is found
-        let mut trigger_found = false;
-        if account_matched {
-            if keywords.iter().any(|&k| content_to_search.contains(k)) {
-                trigger_found = true;
-            }
-        }
-
-        if trigger_found {
-            println!("[{}] 🔥 CZ Binance trigger detected! Opening 5-second buy window.", now);
-            state.last_cz_tweet_time = Some(Instant::now());
-            
-            let _ = msg.channel_id.say(&ctx.http, "🔥 CZ Binance trigger detected! Sniper window OPEN for 5s.").await;
+#[tokio::main]
+async fn main() -> Result<()> {
+    dotenv::dotenv().ok();
+    env_logger::init();
 
-            let state_for_bsc = Arc::clone(&self.state);
-            let http_for_bsc = Arc::clone(&ctx.http);
-            let channel_id_for_bsc = state.channel_id;
+    let token = env::var("DISCORD_TOKEN").expect("Expected DISCORD_TOKEN in environment");
 
-            tokio::spawn(async move {
-                // Monitor for 10 seconds after detection
-                if let Err(e) = monitor::run_log_monitor(
-                    monitor::MonitorMode::Sniper {
-                        state: state_for_bsc,
-                        discord_http: http_for_bsc,
-                        channel_id: channel_id_for_bsc,
-                    },
-                    Some(Duration::from_secs(10))
-                ).await {
-                    eprintln!("BSC Log Monitor error: {:?}", e);
-                }
-            });
-            
-            // Log when the window closes
-            tokio::spawn(async move {
-                tokio::time::sleep(Duration::from_secs(5)).await;
-                let now_close = chrono::Local::now().format("%Y-%m-%d %H:%M:%S").to_string();
-                println!("[{}] ⏱️ 5-second buy window has closed.", now_close);
-            });
-        } else {
-            let mut log_name = if !msg.content.is_empty() {
-                msg.content.clone()
-            } else if let Some(first_embed) = msg.embeds.first() {
-                first_embed.title.clone().unwrap_or_else(|| "Unknown Embed".to_string())
-            } else {
-                "Empty Message".to_string()
-            };
+    // Unlock keystores before any task runs; a bot that cannot sign must not start
+    let wallets = wallets::init()?;
+    println!("Loaded {} wallet(s), primary {:?} via {}", wallets.wallets.len(), wallets.primary().address(), wallets.primary().signer.describe());
+    let triggers = TriggerConfig::from_env()?;
 
-            // Truncate long content for cleaner terminal logs
-            if log_name.len() > 100 {
-                log_name.truncate(97);
-                log_name.push_str("...");
-            }
+    let state = Arc::new(Mutex::new(SharedState::new(triggers)));
 
-            println!("[{}] [Debug] Ignored: '{}'", now, log_name);
+    // Prometheus scrape and health endpoints for the systemd deployment
+    HEALTH.mark_started();
+    let metrics_addr = env::var("METRICS_ADDR").unwrap_or_else(|_| "127.0.0.1:9898".to_string());
+    tokio::spawn(async move {
+        if let Err(e) = server::serve(&metrics_addr).await {
+            eprintln!("HTTP server error: {:?}", e);
         }
+    });
+    tokio::spawn(health::run_probe(Duration::from_secs(15)));
+    if env::var("WATCHDOG_ENABLED").map(|v| v != "false").unwrap_or(true) {
+        tokio::spawn(health::run_watchdog(health::Thresholds::from_env(), Duration::from_secs(15)));
     }
 
-    async fn ready(&self, ctx: Context, ready: Ready) {
-        let now = chrono::Local::now().format("%Y-%m-%d %H:%M:%S").to_string();
-        println!("[{}] ✅ Bot connected as: {}", now, ready.user.name);
-        println!("[{}] 🔍 ID: {}", now, ready.user.id);
-        
-        let state = self.state.lock().await;
-        println!("[{}] 📡 Monitoring Channel ID: {}", now, state.channel_id);
-        
-        // Notify Discord that the bot is alive
-        let channel = serenity::model::id::ChannelId::new(state.channel_id);
-        let _ = channel.say(&ctx.http, "online").await;
-    }
-}
+    tokio::spawn(reputation::run_saver(Arc::clone(&state), Duration::from_secs(60)));
 
-#[tokio::main]
-async fn main() -> Result<()> {
-    dotenv::dotenv().ok();
-    env_logger::init();
-
-    let token = env::var("DISCORD_TOKEN").expect("Expected DISCORD_TOKEN in environment");
-    let channel_id: u64 = env::var("DISCORD_CHANNEL_ID")
-        .expect("Expected DISCORD_CHANNEL_ID in environment")
-        .parse()
-        .expect("Channel ID must be a number");
+    // Every source feeds one pipeline, which owns the buy window and the monitors
+    let (events, trigger_rx) = mpsc::channel(64);
+    let http = Arc::new(Http::new(&token));
+    tokio::spawn(triggers::dispatch(Arc::clone(&state), Arc::clone(&http), trigger_rx));
 
-    let state = Arc::new(Mutex::new(SharedState {
-        last_cz_tweet_time: None,
-        channel_id,
-    }));
+    let notify_channel = state.lock().await.triggers.notify_channel();
+    monitor::ensure_watchlist_monitor(Arc::clone(&state), Arc::clone(&http), notify_channel).await;
+    migration::spawn_watcher(Arc::clone(&state), http, notify_channel);
 
-    let handler = Handler {
-        state: Arc::clone(&state),
+    let sources = {
+        let state = state.lock().await;
+        let mut sources = feed::sources(&state.triggers);
+        sources.extend(telegram::sources(&state.triggers)?);
+        sources
     };
+    for source in sources {
+        let now = chrono::Local::now().format("%Y-%m-%d %H:%M:%S").to_string();
+        println!("[{}] 📡 Starting trigger source {}", now, source.name());
+        triggers::spawn_source(source, events.clone());
+    }
 
-    let intents = GatewayIntents::GUILD_MESSAGES | GatewayIntents::MESSAGE_CONTENT;
-    let mut client = Client::builder(&token, intents)
-        .event_handler(handler)
-        .await?;
-
-    if let Err(why) = client.start().await {
+    if let Err(why) = Box::new(DiscordSource::new(token, Arc::clone(&state))).run(events).await {
         println!("Client error: {:?}", why);
     }
 
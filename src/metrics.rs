@@ -0,0 +1,165 @@
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+
+use crate::latency::TradeTimings;
+
+// Latency histogram bucket upper bounds in milliseconds
+const LATENCY_BUCKETS_MS: [f64; 12] = [
+    5.0, 10.0, 25.0, 50.0, 100.0, 250.0, 500.0, 1000.0, 2500.0, 5000.0, 10000.0, 30000.0,
+];
+
+#[derive(Debug, Default, Clone)]
+struct Histogram {
+    buckets: [u64; LATENCY_BUCKETS_MS.len()],
+    count: u64,
+    sum: f64,
+}
+
+impl Histogram {
+    fn observe(&mut self, value: f64) {
+        for (i, bound) in LATENCY_BUCKETS_MS.iter().enumerate() {
+            if value <= *bound {
+                self.buckets[i] += 1;
+            }
+        }
+        self.count += 1;
+        self.sum += value;
+    }
+}
+
+pub struct Metrics {
+    pub triggers_seen: AtomicU64,
+    pub triggers_deduplicated: AtomicU64,
+    pub tokens_decoded: AtomicU64,
+    pub decode_failures: AtomicU64,
+    pub logs_rejected: AtomicU64,
+    pub chinese_tokens: AtomicU64,
+    pub buys_attempted: AtomicU64,
+    pub buys_succeeded: AtomicU64,
+    pub buys_reverted: AtomicU64,
+    pub buys_failed: AtomicU64,
+    // Tracked in gwei so it fits an atomic integer
+    pub bnb_spent_gwei: AtomicU64,
+    pub ws_connects: AtomicU64,
+    pub ws_reconnects: AtomicU64,
+    rpc_errors: Mutex<BTreeMap<String, u64>>,
+    latency: Mutex<BTreeMap<&'static str, Histogram>>,
+}
+
+pub static METRICS: Metrics = Metrics::new();
+
+pub fn inc(counter: &AtomicU64) {
+    counter.fetch_add(1, Ordering::Relaxed);
+}
+
+impl Metrics {
+    const fn new() -> Self {
+        Self {
+            triggers_seen: AtomicU64::new(0),
+            triggers_deduplicated: AtomicU64::new(0),
+            tokens_decoded: AtomicU64::new(0),
+            decode_failures: AtomicU64::new(0),
+            logs_rejected: AtomicU64::new(0),
+            chinese_tokens: AtomicU64::new(0),
+            buys_attempted: AtomicU64::new(0),
+            buys_succeeded: AtomicU64::new(0),
+            buys_reverted: AtomicU64::new(0),
+            buys_failed: AtomicU64::new(0),
+            bnb_spent_gwei: AtomicU64::new(0),
+            ws_connects: AtomicU64::new(0),
+            ws_reconnects: AtomicU64::new(0),
+            rpc_errors: Mutex::new(BTreeMap::new()),
+            latency: Mutex::new(BTreeMap::new()),
+        }
+    }
+
+    pub fn rpc_error(&self, method: &str) {
+        let mut errors = self.rpc_errors.lock().unwrap();
+        *errors.entry(method.to_string()).or_default() += 1;
+    }
+
+    pub fn bnb_spent(&self, wei: alloy::primitives::U256) {
+        let gwei = wei / alloy::primitives::U256::from(1_000_000_000u64);
+        self.bnb_spent_gwei.fetch_add(gwei.saturating_to::<u64>(), Ordering::Relaxed);
+    }
+
+    pub fn observe_trade(&self, timings: &TradeTimings) {
+        let mut latency = self.latency.lock().unwrap();
+        for (stage, ms) in timings.breakdown() {
+            latency.entry(stage).or_default().observe(ms);
+        }
+    }
+
+    // Renders everything in the Prometheus text exposition format
+    pub fn render(&self) -> String {
+        let mut out = String::new();
+        let counters: [(&str, &str, &AtomicU64); 12] = [
+            ("sniper_triggers_seen_total", "Tweet triggers that opened a buy window", &self.triggers_seen),
+            ("sniper_triggers_deduplicated_total", "Repeat deliveries of a tweet merged into an existing window", &self.triggers_deduplicated),
+            ("sniper_tokens_decoded_total", "TokenCreate logs decoded", &self.tokens_decoded),
+            ("sniper_decode_failures_total", "TokenCreate logs that failed to decode", &self.decode_failures),
+            ("sniper_logs_rejected_total", "TokenCreate logs emitted by a contract other than an official factory", &self.logs_rejected),
+            ("sniper_chinese_tokens_total", "Decoded tokens with a Chinese name or symbol", &self.chinese_tokens),
+            ("sniper_buys_attempted_total", "Buy transactions attempted", &self.buys_attempted),
+            ("sniper_buys_succeeded_total", "Buy transactions mined successfully", &self.buys_succeeded),
+            ("sniper_buys_reverted_total", "Buy transactions mined but reverted", &self.buys_reverted),
+            ("sniper_buys_failed_total", "Buy transactions that failed before broadcast", &self.buys_failed),
+            ("sniper_ws_connects_total", "WebSocket log subscriptions opened", &self.ws_connects),
+            ("sniper_ws_reconnects_total", "WebSocket log subscriptions re-established after a drop", &self.ws_reconnects),
+        ];
+        for (name, help, value) in counters {
+            let _ = writeln!(out, "# HELP {} {}", name, help);
+            let _ = writeln!(out, "# TYPE {} counter", name);
+            let _ = writeln!(out, "{} {}", name, value.load(Ordering::Relaxed));
+        }
+
+        let _ = writeln!(out, "# HELP sniper_bnb_spent_total BNB committed to buys");
+        let _ = writeln!(out, "# TYPE sniper_bnb_spent_total counter");
+        let _ = writeln!(out, "sniper_bnb_spent_total {}", self.bnb_spent_gwei.load(Ordering::Relaxed) as f64 / 1e9);
+
+        let _ = writeln!(out, "# HELP sniper_rpc_errors_total RPC errors by method");
+        let _ = writeln!(out, "# TYPE sniper_rpc_errors_total counter");
+        for (method, count) in self.rpc_errors.lock().unwrap().iter() {
+            let _ = writeln!(out, "sniper_rpc_errors_total{{method=\"{}\"}} {}", method, count);
+        }
+
+        let _ = writeln!(out, "# HELP sniper_latency_ms Per-stage trade latency in milliseconds");
+        let _ = writeln!(out, "# TYPE sniper_latency_ms histogram");
+        for (stage, hist) in self.latency.lock().unwrap().iter() {
+            for (bound, count) in LATENCY_BUCKETS_MS.iter().zip(hist.buckets.iter()) {
+                let _ = writeln!(out, "sniper_latency_ms_bucket{{stage=\"{}\",le=\"{}\"}} {}", stage, bound, count);
+            }
+            let _ = writeln!(out, "sniper_latency_ms_bucket{{stage=\"{}\",le=\"+Inf\"}} {}", stage, hist.count);
+            let _ = writeln!(out, "sniper_latency_ms_sum{{stage=\"{}\"}} {}", stage, hist.sum);
+            let _ = writeln!(out, "sniper_latency_ms_count{{stage=\"{}\"}} {}", stage, hist.count);
+        }
+
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_counters_and_histogram() {
+        let metrics = Metrics::new();
+        inc(&metrics.triggers_seen);
+        metrics.rpc_error("eth_sendRawTransaction");
+        metrics.bnb_spent(alloy::primitives::U256::from(100_000_000_000_000_000u64));
+
+        let timings = TradeTimings::new(None, Some(40));
+        metrics.observe_trade(&timings);
+
+        let text = metrics.render();
+        assert!(text.contains("sniper_triggers_seen_total 1\n"));
+        assert!(text.contains("sniper_bnb_spent_total 0.1\n"));
+        assert!(text.contains("sniper_rpc_errors_total{method=\"eth_sendRawTransaction\"} 1\n"));
+        assert!(text.contains("sniper_latency_ms_bucket{stage=\"relay\",le=\"25\"} 0\n"));
+        assert!(text.contains("sniper_latency_ms_bucket{stage=\"relay\",le=\"50\"} 1\n"));
+        assert!(text.contains("sniper_latency_ms_count{stage=\"relay\"} 1\n"));
+    }
+}
@@ -0,0 +1,214 @@
+use alloy::{
+    primitives::{Address, Log, U256},
+    providers::{Provider, ProviderBuilder},
+    rpc::types::eth::Filter,
+    sol,
+    sol_types::SolEvent,
+};
+use futures_util::stream::StreamExt;
+use std::collections::HashSet;
+use std::env;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::Mutex;
+use anyhow::{Result, anyhow};
+
+use crate::{SharedState, curve, decoder, honeypot::{self, PancakeRouter, WBNB}, journal, notify::{Notifier, TradeReport}, swap, metrics::{self, METRICS}};
+
+sol!(
+    contract PancakeFactoryEvents {
+        event PairCreated(address indexed token0, address indexed token1, address pair, uint256 index);
+    }
+);
+
+sol!(
+    contract TokenManagerEvents {
+        // Emitted when a bonding curve completes and its liquidity moves to PancakeSwap
+        event LiquidityAdded(address base, uint256 offers, address quote, uint256 funds);
+    }
+);
+
+// How often the set of held tokens is rebuilt from the journal
+const HELD_REFRESH: Duration = Duration::from_secs(60);
+
+// Where a token trades: on its four.meme bonding curve, or on PancakeSwap after migration
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Venue {
+    Curve,
+    Pancake,
+}
+
+pub async fn venue(token: Address) -> Result<Venue> {
+    let state = curve::Curve::from_env()?.state(token).await?;
+    Ok(if state.migrated { Venue::Pancake } else { Venue::Curve })
+}
+
+// BNB that selling `units` would return on the token's current venue
+pub async fn quote_sell(token: Address, units: U256) -> Result<U256> {
+    match venue(token).await? {
+        Venue::Curve => Ok(curve::Curve::from_env()?.quote_sell(token, units).await?.net()),
+        Venue::Pancake => {
+            let provider = ProviderBuilder::new().on_builtin(&env::var("BSC_RPC_URL")?).await?;
+            let amounts = PancakeRouter::new(honeypot::router()?, provider)
+                .getAmountsOut(units, vec![token, WBNB])
+                .call()
+                .await?
+                .amounts;
+            amounts.last().copied().ok_or_else(|| anyhow!("Empty Pancake quote for {:?}", token))
+        }
+    }
+}
+
+// The token a migration log is about: the non-WBNB side of a new Pancake pair, or a four.meme LiquidityAdded base
+pub fn migrated_token(log: &Log) -> Option<Address> {
+    if let Ok(pair) = PancakeFactoryEvents::PairCreated::decode_log(log, true) {
+        return if pair.token1 == WBNB && pair.token0 != WBNB {
+            Some(pair.token0)
+        } else if pair.token0 == WBNB && pair.token1 != WBNB {
+            Some(pair.token1)
+        } else {
+            None
+        };
+    }
+    TokenManagerEvents::LiquidityAdded::decode_log(log, true).ok().map(|added| added.base)
+}
+
+// Tokens we have bought this session or according to the journal
+async fn held_tokens(state: &Arc<Mutex<SharedState>>) -> HashSet<Address> {
+    let mut held: HashSet<Address> = state.lock().await.bought.iter().filter_map(|t| t.parse().ok()).collect();
+    match journal::load(&journal::path()) {
+        Ok(entries) => held.extend(journal::positions(&entries).iter().filter(|p| p.buys > 0).filter_map(|p| p.token.parse::<Address>().ok())),
+        Err(e) => eprintln!("Failed to read trade journal for the migration watcher: {:?}", e),
+    }
+    held
+}
+
+async fn on_migration(state: &Arc<Mutex<SharedState>>, notifier: &Notifier, token: Address) {
+    let token_hex = format!("{:?}", token);
+    if !state.lock().await.migrated.insert(token_hex.clone()) {
+        return;
+    }
+    let now = chrono::Local::now().format("%Y-%m-%d %H:%M:%S").to_string();
+    let balance = swap::token_balance(&token_hex).await.map(swap::to_units).unwrap_or_default();
+    println!("[{}] 🎓 {} migrated to PancakeSwap (holding {:.2})", now, token_hex, balance);
+    notifier.notice(
+        "Token migrated to PancakeSwap",
+        &format!("`{}` completed its bonding curve. Holding {:.2}; sells and pricing now use PancakeSwap.", token_hex, balance),
+    ).await;
+
+    let amount = env::var("MIGRATION_BUY_BNB").ok().and_then(|v| v.parse::<f64>().ok()).unwrap_or(0.0);
+    if amount <= 0.0 {
+        return;
+    }
+    let funds = match swap::to_wei(amount) {
+        Ok(funds) => funds,
+        Err(e) => {
+            eprintln!("[{}] Invalid MIGRATION_BUY_BNB {}: {:?}", now, amount, e);
+            return;
+        }
+    };
+    match honeypot::check(token, funds).await {
+        Ok(None) => {}
+        Ok(Some(reason)) => {
+            println!("[{}] Not buying {} at listing: {}", now, token_hex, reason);
+            return;
+        }
+        Err(e) => {
+            notifier.alert(&format!("Listing check failed for {}", token_hex), &format!("{:?}", e)).await;
+            return;
+        }
+    }
+    // A manual /buy or a second watcher event may have raced us here
+    if !state.lock().await.claim_listing_buy(&token_hex) {
+        println!("[{}] Listing buy for {} already claimed. Skipping.", now, token_hex);
+        return;
+    }
+    metrics::inc(&METRICS.buys_attempted);
+    match swap::execute_pancake_buy(&token_hex, amount).await {
+        Ok(outcomes) => {
+            for outcome in &outcomes {
+                METRICS.bnb_spent(outcome.funds);
+                notifier.trade(TradeReport::buy_leg(&token_hex, outcome)).await;
+            }
+        }
+        Err(e) => {
+            metrics::inc(&METRICS.buys_failed);
+            notifier.alert(&format!("Listing buy failed for {}", token_hex), &format!("{:?}", e)).await;
+        }
+    }
+}
+
+pub async fn run_watcher(state: Arc<Mutex<SharedState>>, notifier: Notifier) -> Result<()> {
+    let rpc_url = env::var("BSC_WS_URL").unwrap_or_else(|_| "wss://bsc-rpc.publicnode.com".to_string());
+    let mut addresses = decoder::factories()?;
+    addresses.push(honeypot::factory()?);
+    let filter = Filter::new()
+        .address(addresses)
+        .event_signature(vec![PancakeFactoryEvents::PairCreated::SIGNATURE_HASH, TokenManagerEvents::LiquidityAdded::SIGNATURE_HASH]);
+
+    let provider = ProviderBuilder::new()
+        .on_ws(alloy::rpc::client::WsConnect::new(&rpc_url))
+        .await
+        .inspect_err(|_| METRICS.rpc_error("ws_connect"))?;
+    let mut stream = provider.subscribe_logs(&filter).await
+        .inspect_err(|_| METRICS.rpc_error("eth_subscribe"))?
+        .into_stream();
+
+    let now = chrono::Local::now().format("%Y-%m-%d %H:%M:%S").to_string();
+    println!("[{}] Migration watcher started.", now);
+    let mut held = held_tokens(&state).await;
+    let mut refreshed = Instant::now();
+    while let Some(log) = stream.next().await {
+        if refreshed.elapsed() >= HELD_REFRESH {
+            held = held_tokens(&state).await;
+            refreshed = Instant::now();
+        }
+        if let Some(token) = migrated_token(&log.inner) {
+            if held.contains(&token) {
+                on_migration(&state, &notifier, token).await;
+            }
+        }
+    }
+    Err(anyhow!("Migration log subscription closed"))
+}
+
+// Keeps the watcher running for the life of the process
+pub fn spawn_watcher(state: Arc<Mutex<SharedState>>, discord_http: Arc<serenity::http::Http>, channel_id: u64) {
+    if env::var("MIGRATION_WATCHER").is_ok_and(|v| v == "false") {
+        return;
+    }
+    tokio::spawn(async move {
+        let notifier = Notifier::new(discord_http, channel_id);
+        loop {
+            if let Err(e) = run_watcher(Arc::clone(&state), notifier.clone()).await {
+                eprintln!("Migration watcher error: {:?}. Restarting in 5s...", e);
+            }
+            tokio::time::sleep(Duration::from_secs(5)).await;
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy::primitives::{B256, LogData};
+    use alloy::sol_types::SolValue;
+
+    #[test]
+    fn test_migrated_token() {
+        let token = Address::repeat_byte(0x44);
+        let topic = |a: Address| B256::left_padding_from(a.as_slice());
+        let pair_data = (Address::repeat_byte(0x99), U256::from(1)).abi_encode_sequence();
+        let pair = |token0: Address, token1: Address| Log {
+            address: Address::ZERO,
+            data: LogData::new_unchecked(vec![PancakeFactoryEvents::PairCreated::SIGNATURE_HASH, topic(token0), topic(token1)], pair_data.clone().into()),
+        };
+        assert_eq!(migrated_token(&pair(token, WBNB)), Some(token));
+        assert_eq!(migrated_token(&pair(WBNB, token)), Some(token));
+        assert_eq!(migrated_token(&pair(token, Address::repeat_byte(0x55))), None);
+
+        let added = TokenManagerEvents::LiquidityAdded { base: token, offers: U256::ZERO, quote: Address::ZERO, funds: U256::from(24) };
+        let log = Log { address: Address::ZERO, data: added.encode_log_data() };
+        assert_eq!(migrated_token(&log), Some(token));
+    }
+}
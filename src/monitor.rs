@@ -1,6 +1,7 @@
 use alloy::{
-    providers::{Provider, ProviderBuilder},
-    rpc::types::eth::Filter,
+    providers::{Provider, ProviderBuilder, RootProvider},
+    pubsub::{PubSubFrontend, Subscription},
+    rpc::types::eth::{Filter, Log},
     primitives::b256,
 };
 use futures_util::stream::StreamExt;
@@ -10,7 +11,7 @@ use std::time::{Duration, Instant};
 use anyhow::Result;
 use std::env;
 
-use crate::{swap, decoder};
+use crate::{swap, decoder, confirm, inspect, targets, watchlist, latency::TradeTimings, notify::{Notifier, TradeReport}, metrics::{self, METRICS}, health::HEALTH};
 
 pub enum MonitorMode {
     Sniper {
@@ -18,25 +19,57 @@ pub enum MonitorMode {
         discord_http: Arc<serenity::http::Http>,
         channel_id: u64,
     },
+    // Runs indefinitely, buying only watchlist matches
+    Watchlist {
+        state: Arc<Mutex<crate::SharedState>>,
+        discord_http: Arc<serenity::http::Http>,
+        channel_id: u64,
+    },
     MonitorOnly,
 }
 
+async fn subscribe(rpc_url: &str, filter: &Filter) -> Result<(RootProvider<PubSubFrontend>, Subscription<Log>)> {
+    let provider = ProviderBuilder::new()
+        .on_ws(alloy::rpc::client::WsConnect::new(rpc_url))
+        .await
+        .inspect_err(|_| METRICS.rpc_error("ws_connect"))?;
+    let sub = provider.subscribe_logs(filter).await
+        .inspect_err(|_| METRICS.rpc_error("eth_subscribe"))?;
+    metrics::inc(&METRICS.ws_connects);
+    Ok((provider, sub))
+}
+
+// Keeps the health active-monitor count correct on every exit path
+struct ActiveMonitor;
+
+impl ActiveMonitor {
+    fn start() -> Self {
+        HEALTH.monitor_started();
+        ActiveMonitor
+    }
+}
+
+impl Drop for ActiveMonitor {
+    fn drop(&mut self) {
+        HEALTH.monitor_stopped();
+    }
+}
+
 pub async fn run_log_monitor(mode: MonitorMode, timeout_duration: Option<Duration>) -> Result<()> {
     let rpc_url = env::var("BSC_WS_URL")
         .unwrap_or_else(|_| "wss://bsc-rpc.publicnode.com".to_string());
     
     // Note: If you want to use the Quiknode URL, set it in your .env file as BSC_WS_URL
     
-    let provider = ProviderBuilder::new()
-        .on_ws(alloy::rpc::client::WsConnect::new(rpc_url))
-        .await?;
-    
     let cz_topic = b256!("396d5e902b675b032348d3d2e9517ee8f0c4a926603fbc075d3d282ff00cad20");
-    let filter = Filter::new().event_signature(cz_topic);
+    // Only the official factories; the decoder re-checks in case a node ignores the address filter
+    let factories = decoder::factories()?;
+    let filter = Filter::new().address(factories.clone()).event_signature(cz_topic);
     
-    let sub = provider.subscribe_logs(&filter).await?;
+    let (mut provider, sub) = subscribe(&rpc_url, &filter).await?;
     let mut stream = sub.into_stream();
     
+    let _active = ActiveMonitor::start();
     let start_time = Instant::now();
     let now = chrono::Local::now().format("%Y-%m-%d %H:%M:%S").to_string();
     println!("[{}] BSC Log Monitor started for CZ Topic.", now);
@@ -54,31 +87,83 @@ pub async fn run_log_monitor(mode: MonitorMode, timeout_duration: Option<Duratio
             next_log = stream.next() => {
                 let log = match next_log {
                     Some(l) => l,
-                    None => break,
+                    None => {
+                        // The server closed the subscription; re-establish it rather than going blind
+                        let now_drop = chrono::Local::now().format("%Y-%m-%d %H:%M:%S").to_string();
+                        println!("[{}] ⚠️ Log subscription dropped. Reconnecting...", now_drop);
+                        metrics::inc(&METRICS.ws_reconnects);
+                        let (new_provider, sub) = subscribe(&rpc_url, &filter).await?;
+                        provider = new_provider;
+                        stream = sub.into_stream();
+                        continue;
+                    }
                 };
                 
                 let current_time = Instant::now();
+                HEALTH.log_received(log.block_number);
                 let now_log = chrono::Local::now().format("%Y-%m-%d %H:%M:%S").to_string();
                 
                 // 1. Determine if we are in a buy window
-                let in_window = match &mode {
+                let (in_window, confirm, buy_amount_bnb, targets, mut timings) = match &mode {
                     MonitorMode::Sniper { state, .. } => {
                         let state_guard = state.lock().await;
-                        if let Some(last_tweet) = state_guard.last_cz_tweet_time {
-                            current_time.duration_since(last_tweet) < Duration::from_secs(5)
-                        } else {
-                            false
-                        }
+                        (
+                            state_guard.window_open(current_time),
+                            state_guard.confirm_required(current_time),
+                            state_guard.buy_amount_bnb,
+                            state_guard.targets.clone(),
+                            TradeTimings::new(state_guard.last_cz_tweet_time, state_guard.last_trigger_relay_ms),
+                        )
+                    }
+                    MonitorMode::Watchlist { state, .. } => {
+                        let state_guard = state.lock().await;
+                        (true, state_guard.confirm_mode, state_guard.buy_amount_bnb, Vec::new(), TradeTimings::new(None, None))
                     }
-                    MonitorMode::MonitorOnly => true, // Always show details in monitor mode
+                    MonitorMode::MonitorOnly => (true, false, 0.0, Vec::new(), TradeTimings::new(None, None)), // Always show details in monitor mode
                 };
+                timings.log_received_at = current_time;
+                timings.block_number = log.block_number;
+                timings.block_timestamp = log.block_timestamp;
 
                 // 2. Decode and Log
-                match decoder::decode_custom_log(log.data().data.as_ref()) {
-                    Ok((s1, s2)) => {
-                        let is_chinese = decoder::contains_chinese(&s1) || decoder::contains_chinese(&s2);
+                let decoded = decoder::decode_token_create(log.address(), log.data().data.as_ref(), &factories);
+                timings.decoded_at = Some(Instant::now());
+                match decoded {
+                    Ok(token) => {
+                        let (s1, s2) = (&token.name, &token.symbol);
+                        let is_chinese = decoder::contains_chinese(s1) || decoder::contains_chinese(s2);
+                        metrics::inc(&METRICS.tokens_decoded);
+                        if is_chinese {
+                            metrics::inc(&METRICS.chinese_tokens);
+                        }
+                        if let MonitorMode::Sniper { state, .. } | MonitorMode::Watchlist { state, .. } = &mode {
+                            state.lock().await.reputation.record_launch(token.creator, token.token, chrono::Utc::now().timestamp());
+                        }
                         
                         match &mode {
+                            MonitorMode::Watchlist { state, discord_http, channel_id } => {
+                                let hit = state.lock().await.watchlist.matches(&token);
+                                if let Some((kind, entry)) = hit {
+                                    println!("[{}] 👀 Watchlist hit on {:?} '{}': '{}' / '{}' ({})", now_log, kind, entry, s1, s2, token.token_hex());
+                                    // Watching a creator is an explicit vote of trust, so only name and symbol hits check reputation
+                                    if let Some(reason) = buy_blocked(&provider, state, &token, kind != watchlist::Kind::Creator).await {
+                                        println!("[{}] 🚫 Skipping {}: {}", now_log, token.token_hex(), reason);
+                                    } else if confirm {
+                                        confirm::post_candidate(discord_http, *channel_id, state, &token).await;
+                                    } else {
+                                        let bought = buy_and_report(&provider, state, discord_http, *channel_id, &token, buy_amount_bnb, timings).await;
+                                        // A name or symbol is one launch; copycats reusing it must not be bought too.
+                                        // After a failed buy the entry stays so the next match is tried again.
+                                        if bought && kind != watchlist::Kind::Creator {
+                                            let mut guard = state.lock().await;
+                                            let _ = guard.watchlist.remove(kind, &entry);
+                                            if let Err(e) = guard.watchlist.save(&watchlist::path()) {
+                                                eprintln!("[{}] Failed to save watchlist: {:?}", now_log, e);
+                                            }
+                                        }
+                                    }
+                                }
+                            }
                             MonitorMode::MonitorOnly => {
                                 if is_chinese {
                                     println!("[{}] 🚀 [MONITOR] CHINESE DETECTED! strings: '{}', '{}'", now_log, s1, s2);
@@ -86,40 +171,43 @@ pub async fn run_log_monitor(mode: MonitorMode, timeout_duration: Option<Duratio
                                     println!("[{}] [Monitor] Decoded names: '{}' | '{}'", now_log, s1, s2);
                                 }
                             }
-                            MonitorMode::Sniper { discord_http, channel_id, .. } => {
+                            MonitorMode::Sniper { state, discord_http, channel_id } => {
                                 println!("[{}] Decoded strings: '{}', '{}'", now_log, s1, s2);
-                                if is_chinese {
-                                    println!("[{}] Chinese characters detected! EXECUTING BUY.", now_log);
-                                    if in_window {
-                                        if log.data().data.len() >= 64 {
-                                            let token_address = &log.data().data[44..64]; 
-                                            let token_hex = format!("0x{}", hex::encode(token_address));
-                                            println!("[{}] Window active! Buying token: {}", now_log, token_hex);
-                                            
-                                            // Execute Swap and Notify Discord
-                                            match swap::execute_swap(&token_hex).await {
-                                                Ok(_) => {
-                                                    println!("[{}] Swap SUCCESS for {}", now_log, token_hex);
-                                                    let channel = serenity::all::ChannelId::new(*channel_id);
-                                                    let msg = format!("🚀 **SUCCESSFULLY BOUGHT TOKEN!**\nAddress: `{}`", token_hex);
-                                                    let _ = channel.say(discord_http, msg).await;
-                                                }
-                                                Err(e) => {
-                                                    eprintln!("[{}] Swap failed: {:?}", now_log, e);
-                                                    let channel = serenity::all::ChannelId::new(*channel_id);
-                                                    let msg = format!("❌ **Swap Failed** for `{}`\nError: `{:?}`", token_hex, e);
-                                                    let _ = channel.say(discord_http, msg).await;
-                                                }
-                                            }
-                                        }
+                                // With targets from the tweet only those names qualify; otherwise any Chinese name does
+                                let wanted = if targets.is_empty() {
+                                    is_chinese
+                                } else if let Some(target) = targets::matching_target(&targets, &token) {
+                                    println!("[{}] 🎯 Token matches tweet target '{}'", now_log, target);
+                                    true
+                                } else {
+                                    false
+                                };
+                                if wanted {
+                                    if !in_window {
+                                        println!("[{}] Outside buy window. Skipping buy.", now_log);
+                                    } else if let Some(reason) = buy_blocked(&provider, state, &token, true).await {
+                                        println!("[{}] 🚫 Skipping {}: {}", now_log, token.token_hex(), reason);
+                                    } else if confirm {
+                                        println!("[{}] Candidate detected! Asking Discord for confirmation: {}", now_log, token.token_hex());
+                                        confirm::post_candidate(discord_http, *channel_id, state, &token).await;
                                     } else {
-                                        println!("[{}] Outside 5-second window. Skipping buy.", now_log);
+                                        println!("[{}] Candidate detected! EXECUTING BUY.", now_log);
+                                        println!("[{}] Window active! Buying token: {}", now_log, token.token_hex());
+                                        buy_and_report(&provider, state, discord_http, *channel_id, &token, buy_amount_bnb, timings).await;
                                     }
                                 }
                             }
                         }
                     }
-                    Err(e) => eprintln!("[{}] Failed to decode log: {:?}", now_log, e),
+                    Err(e) => {
+                        if factories.contains(&log.address()) {
+                            metrics::inc(&METRICS.decode_failures);
+                            eprintln!("[{}] Failed to decode log: {:?}", now_log, e);
+                        } else {
+                            metrics::inc(&METRICS.logs_rejected);
+                            eprintln!("[{}] 🚨 Rejected spoofed log in tx {:?}: {}", now_log, log.transaction_hash, e);
+                        }
+                    }
                 }
             }
             _ = tokio::time::sleep(Duration::from_millis(500)) => {
@@ -130,3 +218,166 @@ pub async fn run_log_monitor(mode: MonitorMode, timeout_duration: Option<Duratio
     
     Ok(())
 }
+
+// Why a candidate must not be bought, if anything: creator reputation, then the token's bytecode
+// Sniper and watchlist buys go into the four.meme bonding curve, which the PancakeSwap honeypot
+// simulation cannot cover; they rely on the creator and bytecode checks here instead
+async fn buy_blocked(
+    provider: &RootProvider<PubSubFrontend>,
+    state: &Arc<Mutex<crate::SharedState>>,
+    token: &decoder::TokenCreate,
+    check_creator: bool,
+) -> Option<String> {
+    if check_creator {
+        let reason = state.lock().await.reputation.block_reason(&token.creator, chrono::Utc::now().timestamp());
+        if reason.is_some() {
+            return reason;
+        }
+    }
+    match inspect::check(provider, token.token).await {
+        Ok(reason) => reason,
+        Err(e) => Some(format!("bytecode inspection failed: {}", e)),
+    }
+}
+
+// Marks the creator as a dumper if most of their allocation is gone shortly after our buy
+fn spawn_dump_check(state: Arc<Mutex<crate::SharedState>>, token: decoder::TokenCreate) {
+    let delay = env::var("DUMP_CHECK_SECS").ok().and_then(|v| v.parse().ok()).unwrap_or(120);
+    tokio::spawn(async move {
+        let Ok(initial) = swap::balance_of(token.token, token.creator).await else {
+            return;
+        };
+        if initial.is_zero() {
+            return;
+        }
+        tokio::time::sleep(Duration::from_secs(delay)).await;
+        let Ok(remaining) = swap::balance_of(token.token, token.creator).await else {
+            return;
+        };
+        if remaining * alloy::primitives::U256::from(10) < initial {
+            let now = chrono::Local::now().format("%Y-%m-%d %H:%M:%S").to_string();
+            println!("[{}] 📉 Creator {:?} dumped {} within {}s", now, token.creator, token.token_hex(), delay);
+            state.lock().await.reputation.mark_dumped(token.creator);
+        }
+    });
+}
+
+// Executes the swap, records latency and notifies Discord; false if no buy went out
+async fn buy_and_report(
+    provider: &RootProvider<PubSubFrontend>,
+    state: &Arc<Mutex<crate::SharedState>>,
+    discord_http: &Arc<serenity::http::Http>,
+    channel_id: u64,
+    token: &decoder::TokenCreate,
+    buy_amount_bnb: f64,
+    mut timings: TradeTimings,
+) -> bool {
+    let now_log = chrono::Local::now().format("%Y-%m-%d %H:%M:%S").to_string();
+    let notifier = Notifier::new(Arc::clone(discord_http), channel_id);
+    let token_hex = token.token_hex();
+
+    // Another path already holds it, which counts as bought
+    if !state.lock().await.claim_buy(&token_hex) {
+        println!("[{}] Already bought {}; skipping duplicate buy.", now_log, token_hex);
+        return true;
+    }
+    metrics::inc(&METRICS.buys_attempted);
+    match swap::execute_swap(&token_hex, buy_amount_bnb).await {
+        Ok(outcomes) => {
+            println!("[{}] Swap SUCCESS for {} ({} wallet(s))", now_log, token_hex, outcomes.len());
+            spawn_dump_check(Arc::clone(state), token.clone());
+            // Latency is measured on the first leg; split buys go out in parallel
+            if let Some(first) = outcomes.first() {
+                timings.swap_started_at = Some(first.started_at);
+                timings.signed_at = Some(first.signed_at);
+                timings.broadcast_at = Some(first.broadcast_at);
+            }
+
+            // Block timestamp lookup happens after the broadcast so it never delays the buy
+            if timings.block_timestamp.is_none() {
+                if let Some(number) = timings.block_number {
+                    if let Ok(Some(block)) = provider.get_block_by_number(number.into(), false).await {
+                        timings.block_timestamp = Some(block.header.timestamp);
+                    }
+                }
+            }
+
+            let report = {
+                let mut state_guard = state.lock().await;
+                state_guard.latency.record(&timings);
+                METRICS.observe_trade(&timings);
+                state_guard.latency.report()
+            };
+            println!("[{}] [Latency] {}", now_log, timings.summary());
+            println!("[{}] [Latency] Rolling percentiles:\n{}", now_log, report);
+
+            for outcome in &outcomes {
+                METRICS.bnb_spent(outcome.funds);
+                notifier.trade(TradeReport {
+                    name: Some(token.name.clone()),
+                    symbol: Some(token.symbol.clone()),
+                    creator: Some(token.creator),
+                    latency: Some(timings.summary()),
+                    ..TradeReport::buy_leg(&token_hex, outcome)
+                }).await;
+            }
+            true
+        }
+        Err(e) => {
+            metrics::inc(&METRICS.buys_failed);
+            eprintln!("[{}] Swap failed: {:?}", now_log, e);
+            notifier.alert(&format!("Swap failed for {} ({})", token.name, token_hex), &format!("{:?}", e)).await;
+            false
+        }
+    }
+}
+
+// Runs a sniper-mode monitor in the background for `duration`
+pub fn spawn_sniper(
+    state: Arc<Mutex<crate::SharedState>>,
+    discord_http: Arc<serenity::http::Http>,
+    channel_id: u64,
+    duration: Duration,
+) {
+    tokio::spawn(async move {
+        let notifier = Notifier::new(Arc::clone(&discord_http), channel_id);
+        if let Err(e) = run_log_monitor(
+            MonitorMode::Sniper { state, discord_http, channel_id },
+            Some(duration),
+        ).await {
+            eprintln!("BSC Log Monitor error: {:?}", e);
+            notifier.alert("BSC Log Monitor error", &format!("{:?}", e)).await;
+        }
+    });
+}
+
+// Starts the always-on watchlist monitor unless it is already running
+pub async fn ensure_watchlist_monitor(
+    state: Arc<Mutex<crate::SharedState>>,
+    discord_http: Arc<serenity::http::Http>,
+    channel_id: u64,
+) {
+    {
+        let mut guard = state.lock().await;
+        if guard.watchlist_monitor || guard.watchlist.is_empty() {
+            return;
+        }
+        guard.watchlist_monitor = true;
+    }
+
+    tokio::spawn(async move {
+        let notifier = Notifier::new(Arc::clone(&discord_http), channel_id);
+        loop {
+            let mode = MonitorMode::Watchlist {
+                state: Arc::clone(&state),
+                discord_http: Arc::clone(&discord_http),
+                channel_id,
+            };
+            if let Err(e) = run_log_monitor(mode, None).await {
+                eprintln!("Watchlist monitor error: {:?}. Restarting in 5s...", e);
+                notifier.alert("Watchlist monitor error", &format!("{:?}", e)).await;
+            }
+            tokio::time::sleep(Duration::from_secs(5)).await;
+        }
+    });
+}
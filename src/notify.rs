@@ -0,0 +1,206 @@
+use alloy::primitives::{Address, B256, U256};
+use serenity::all::{ChannelId, Colour, CreateEmbed, CreateEmbedFooter, CreateMessage, EditMessage, MessageId, Timestamp};
+use std::env;
+use std::sync::Arc;
+
+use crate::{journal::Side, swap::{self, Fill}};
+
+const BSCSCAN: &str = "https://bscscan.com";
+
+#[derive(Debug, Clone)]
+pub struct TradeReport {
+    pub side: Side,
+    pub token: String,
+    pub name: Option<String>,
+    pub symbol: Option<String>,
+    pub creator: Option<Address>,
+    pub tx_hash: B256,
+    // Sending wallet; the primary one when unset
+    pub wallet: Option<Address>,
+    // BNB spent on a buy
+    pub bnb: Option<f64>,
+    // Token units sent on a sell
+    pub tokens: Option<U256>,
+    pub latency: Option<String>,
+}
+
+impl TradeReport {
+    pub fn buy(token: &str, tx_hash: B256, bnb: f64) -> Self {
+        Self {
+            side: Side::Buy,
+            token: token.to_string(),
+            name: None,
+            symbol: None,
+            creator: None,
+            tx_hash,
+            wallet: None,
+            bnb: Some(bnb),
+            tokens: None,
+            latency: None,
+        }
+    }
+
+    // One wallet's leg of a buy
+    pub fn buy_leg(token: &str, outcome: &swap::SwapOutcome) -> Self {
+        Self { wallet: Some(outcome.wallet), ..Self::buy(token, outcome.tx_hash, swap::to_units(outcome.funds)) }
+    }
+
+    pub fn sell_leg(token: &str, outcome: &swap::SellOutcome) -> Self {
+        Self { wallet: Some(outcome.wallet), ..Self::sell(token, outcome.tx_hash, outcome.amount) }
+    }
+
+    pub fn sell(token: &str, tx_hash: B256, tokens: U256) -> Self {
+        Self {
+            side: Side::Sell,
+            tokens: Some(tokens),
+            bnb: None,
+            ..Self::buy(token, tx_hash, 0.0)
+        }
+    }
+}
+
+// Posts trade embeds to the main channel and errors to a separate alerts channel
+#[derive(Clone)]
+pub struct Notifier {
+    http: Arc<serenity::http::Http>,
+    channel_id: ChannelId,
+    alerts_channel_id: Option<ChannelId>,
+}
+
+impl Notifier {
+    pub fn new(http: Arc<serenity::http::Http>, channel_id: u64) -> Self {
+        let alerts_channel_id = env::var("DISCORD_ALERTS_CHANNEL_ID")
+            .ok()
+            .and_then(|id| id.parse().ok())
+            .map(ChannelId::new);
+        Self { http, channel_id: ChannelId::new(channel_id), alerts_channel_id }
+    }
+
+    pub async fn alert(&self, title: &str, detail: &str) {
+        let embed = CreateEmbed::new()
+            .title(format!("⚠️ {}", title))
+            .description(format!("```\n{}\n```", truncate(detail, 3900)))
+            .colour(Colour::RED)
+            .timestamp(Timestamp::now());
+        let channel = self.alerts_channel_id.unwrap_or(self.channel_id);
+        if let Err(e) = channel.send_message(&self.http, CreateMessage::new().embed(embed)).await {
+            eprintln!("Failed to post alert '{}': {:?}", title, e);
+        }
+    }
+
+    pub async fn notice(&self, title: &str, detail: &str) {
+        let embed = CreateEmbed::new()
+            .title(title)
+            .description(truncate(detail, 3900))
+            .colour(Colour::BLUE)
+            .timestamp(Timestamp::now());
+        if let Err(e) = self.channel_id.send_message(&self.http, CreateMessage::new().embed(embed)).await {
+            eprintln!("Failed to post notice '{}': {:?}", title, e);
+        }
+    }
+
+    // Posts the trade as soon as it is broadcast, then edits it in place once the receipt lands
+    pub async fn trade(&self, report: TradeReport) {
+        let message = match self.channel_id.send_message(&self.http, CreateMessage::new().embed(trade_embed(&report, None))).await {
+            Ok(message) => Some(message.id),
+            Err(e) => {
+                eprintln!("Failed to post trade notification: {:?}", e);
+                None
+            }
+        };
+
+        let notifier = self.clone();
+        tokio::spawn(async move {
+            let result = swap::settle(report.tx_hash, report.side, &report.token, report.bnb, report.wallet).await;
+            match &result {
+                Ok(Some(fill)) => notifier.update(message, trade_embed(&report, Some(fill))).await,
+                Ok(None) => notifier.alert("No receipt", &format!("{:?} was not mined within 60s", report.tx_hash)).await,
+                Err(e) => notifier.alert("Receipt tracking failed", &format!("{:?}: {:?}", report.tx_hash, e)).await,
+            }
+            if let Ok(Some(fill)) = &result {
+                if !fill.success {
+                    notifier.alert("Trade reverted", &format!("{:?} for {}", report.tx_hash, report.token)).await;
+                }
+            }
+        });
+    }
+
+    async fn update(&self, message: Option<MessageId>, embed: CreateEmbed) {
+        let result = match message {
+            Some(id) => self.channel_id.edit_message(&self.http, id, EditMessage::new().embed(embed)).await.map(|_| ()),
+            None => self.channel_id.send_message(&self.http, CreateMessage::new().embed(embed)).await.map(|_| ()),
+        };
+        if let Err(e) = result {
+            eprintln!("Failed to update trade notification: {:?}", e);
+        }
+    }
+}
+
+fn truncate(s: &str, max: usize) -> String {
+    if s.len() <= max {
+        return s.to_string();
+    }
+    let mut end = max;
+    while !s.is_char_boundary(end) {
+        end -= 1;
+    }
+    format!("{}...", &s[..end])
+}
+
+pub fn trade_embed(report: &TradeReport, fill: Option<&Fill>) -> CreateEmbed {
+    let (verb, colour) = match (report.side, fill.map(|f| f.success)) {
+        (_, Some(false)) => ("REVERTED", Colour::RED),
+        (Side::Buy, Some(true)) => ("Bought", Colour::DARK_GREEN),
+        (Side::Sell, Some(true)) => ("Sold", Colour::DARK_GREEN),
+        (Side::Buy, None) => ("Buy sent", Colour::GOLD),
+        (Side::Sell, None) => ("Sell sent", Colour::GOLD),
+    };
+    let label = match (&report.name, &report.symbol) {
+        (Some(name), Some(symbol)) => format!("{} ({})", name, symbol),
+        (Some(name), None) => name.clone(),
+        _ => report.token.clone(),
+    };
+
+    let mut embed = CreateEmbed::new()
+        .title(format!("{} {}", if fill.is_some_and(|f| !f.success) { "❌" } else { "🚀" }, verb))
+        .description(format!("**{}**", label))
+        .url(format!("{}/tx/{:?}", BSCSCAN, report.tx_hash))
+        .colour(colour)
+        .field("Token", format!("[`{}`]({}/token/{})", report.token, BSCSCAN, report.token), false)
+        .timestamp(Timestamp::now());
+
+    if let Some(creator) = report.creator {
+        embed = embed.field("Creator", format!("[`{:?}`]({}/address/{:?})", creator, BSCSCAN, creator), false);
+    }
+    if let Some(wallet) = report.wallet {
+        embed = embed.field("Wallet", format!("[`{:?}`]({}/address/{:?})", wallet, BSCSCAN, wallet), false);
+    }
+    embed = embed.field("Tx", format!("[`{:?}`]({}/tx/{:?})", report.tx_hash, BSCSCAN, report.tx_hash), false);
+    if let Some(bnb) = report.bnb {
+        embed = embed.field("BNB spent", format!("{}", bnb), true);
+    }
+    if let Some(tokens) = report.tokens {
+        embed = embed.field("Tokens sent", format!("{:.2}", swap::to_units(tokens)), true);
+    }
+    match fill {
+        Some(fill) => {
+            if report.side == Side::Buy {
+                embed = embed.field("Tokens received", format!("{:.2}", swap::to_units(fill.tokens)), true);
+            }
+            if let Some(bnb) = fill.bnb_received {
+                embed = embed.field("BNB received", format!("{:.6}", swap::to_units(bnb)), true);
+            }
+            embed = embed.field("Gas paid", format!("{:.6} BNB", swap::to_units(fill.gas_paid)), true);
+            if let Some(block) = fill.block_number {
+                embed = embed.field("Block", block.to_string(), true);
+            }
+        }
+        None => {
+            embed = embed.footer(CreateEmbedFooter::new("Waiting for receipt..."));
+        }
+    }
+    if let Some(latency) = &report.latency {
+        embed = embed.field("Latency", format!("`{}`", latency), false);
+    }
+    embed
+}
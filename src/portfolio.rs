@@ -0,0 +1,193 @@
+use alloy::{
+    primitives::{Address, U256},
+    providers::{Provider, ProviderBuilder},
+};
+use std::collections::HashMap;
+use std::env;
+use anyhow::Result;
+
+use crate::{journal::{self, JournalEntry, Position, Side, Status}, migration::{self, Venue}, swap, metrics::METRICS};
+
+// Profit and loss in BNB for one token, at average cost
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct Pnl {
+    pub realised: f64,
+    // None when the holding could not be priced
+    pub unrealised: Option<f64>,
+}
+
+// Splits a position's cost between what was sold and what is still held. Without token
+// amounts (buys that never settled) the whole spend stays with the holding. Sells with
+// unknown proceeds leave the holding but are kept out of realised PnL.
+pub fn position_pnl(position: &Position, value_bnb: Option<f64>) -> Pnl {
+    let cost_of = |tokens: f64| if position.tokens_bought > 0.0 {
+        position.bnb_spent * (tokens / position.tokens_bought).min(1.0)
+    } else {
+        0.0
+    };
+    let sold_cost = cost_of(position.tokens_sold);
+    let priced_cost = cost_of(position.tokens_sold - position.tokens_sold_unpriced);
+    Pnl {
+        realised: position.bnb_received - priced_cost,
+        unrealised: value_bnb.map(|value| value - (position.bnb_spent - sold_cost)),
+    }
+}
+
+// Realised PnL of each sell against the running average cost of earlier buys
+pub fn trade_pnl(entries: &[JournalEntry]) -> Vec<(JournalEntry, Option<f64>)> {
+    // Per token: (BNB spent, tokens held) for the running average
+    let mut books: HashMap<&str, (f64, f64)> = HashMap::new();
+    let mut trades = Vec::new();
+    for entry in entries.iter().filter(|e| e.status != Status::Reverted) {
+        let (cost, held) = books.entry(&entry.token).or_default();
+        let pnl = match entry.side {
+            Side::Buy => {
+                *cost += entry.bnb.unwrap_or_default();
+                *held += entry.tokens.unwrap_or_default();
+                None
+            }
+            Side::Sell => match entry.tokens {
+                Some(tokens) if *held > 0.0 => {
+                    let sold_cost = *cost * (tokens / *held).min(1.0);
+                    *cost -= sold_cost;
+                    *held = (*held - tokens).max(0.0);
+                    // Unknown proceeds still reduce the book, but are not booked as a loss
+                    entry.bnb.map(|received| received - sold_cost)
+                }
+                _ => None,
+            },
+        };
+        trades.push((entry.clone(), pnl));
+    }
+    trades
+}
+
+#[derive(Debug, Clone)]
+pub struct Holding {
+    pub position: Position,
+    pub balance: f64,
+    pub venue: Option<Venue>,
+    pub value_bnb: Option<f64>,
+    pub pnl: Pnl,
+}
+
+#[derive(Debug, Clone)]
+pub struct Portfolio {
+    pub wallets: Vec<Address>,
+    pub bnb_balance: f64,
+    pub holdings: Vec<Holding>,
+}
+
+impl Portfolio {
+    pub fn total(&self) -> Pnl {
+        self.holdings.iter().fold(Pnl { realised: 0.0, unrealised: Some(0.0) }, |total, h| Pnl {
+            realised: total.realised + h.pnl.realised,
+            unrealised: total.unrealised.zip(h.pnl.unrealised).map(|(a, b)| a + b),
+        })
+    }
+
+    pub fn report(&self) -> String {
+        let signed = |v: f64| format!("{:+.4}", v);
+        let mut lines = vec![
+            format!("**Wallets:** {}", self.wallets.iter().map(|w| format!("`{:?}`", w)).collect::<Vec<_>>().join(", ")),
+            format!("**BNB:** {:.4}", self.bnb_balance),
+        ];
+        for h in &self.holdings {
+            let venue = match h.venue {
+                Some(Venue::Curve) => "curve",
+                Some(Venue::Pancake) => "pancake",
+                None => "?",
+            };
+            lines.push(format!(
+                "`{}` ({}) — balance {:.2} | worth {} | spent {:.4} | realised {} | unrealised {}",
+                h.position.token,
+                venue,
+                h.balance,
+                h.value_bnb.map_or("?".to_string(), |v| format!("{:.4} BNB", v)),
+                h.position.bnb_spent,
+                signed(h.pnl.realised),
+                h.pnl.unrealised.map_or("?".to_string(), signed),
+            ));
+        }
+        let total = self.total();
+        lines.push(format!(
+            "**Total PnL:** realised {} BNB | unrealised {} BNB",
+            signed(total.realised),
+            total.unrealised.map_or("? (some holdings unpriced)".to_string(), |v| format!("{} BNB", signed(v))),
+        ));
+        lines.join("\n")
+    }
+}
+
+// Every token in the journal with its balance summed across `wallets`, priced on its current venue
+pub async fn snapshot(wallets: &[Address]) -> Result<Portfolio> {
+    let provider = ProviderBuilder::new().on_builtin(&env::var("BSC_RPC_URL")?).await?;
+    let mut bnb_balance = 0.0;
+    for wallet in wallets {
+        bnb_balance += swap::to_units(
+            provider.get_balance(*wallet).await.inspect_err(|_| METRICS.rpc_error("eth_getBalance"))?,
+        );
+    }
+
+    let entries = journal::load(&journal::path())?;
+    let mut holdings = Vec::new();
+    for position in journal::positions(&entries) {
+        let token: Address = position.token.parse()?;
+        let mut units = Some(U256::ZERO);
+        for wallet in wallets {
+            units = match (units, swap::balance_of(token, *wallet).await.ok()) {
+                (Some(total), Some(balance)) => Some(total + balance),
+                _ => None,
+            };
+        }
+        let venue = migration::venue(token).await.ok();
+        let value_bnb = match units {
+            Some(units) if units.is_zero() => Some(0.0),
+            Some(units) => migration::quote_sell(token, units).await.ok().map(swap::to_units),
+            None => None,
+        };
+        let pnl = position_pnl(&position, value_bnb);
+        holdings.push(Holding {
+            balance: units.map(swap::to_units).unwrap_or_default(),
+            venue,
+            value_bnb,
+            pnl,
+            position,
+        });
+    }
+    Ok(Portfolio { wallets: wallets.to_vec(), bnb_balance, holdings })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy::primitives::B256;
+
+    #[test]
+    fn test_pnl() {
+        let token = "0xabc0000000000000000000000000000000004444";
+        let settled = |side, hash, bnb, tokens| JournalEntry { status: Status::Confirmed, ..JournalEntry::new(side, token, B256::repeat_byte(hash), Some(bnb), Some(tokens)) };
+        let entries = vec![
+            settled(Side::Buy, 1, 0.1, 1000.0),
+            settled(Side::Buy, 2, 0.3, 1000.0),
+            settled(Side::Sell, 3, 0.5, 1000.0),
+            JournalEntry { status: Status::Reverted, ..settled(Side::Sell, 4, 9.0, 1000.0) },
+            // Sold without known proceeds: neither a gain nor a loss
+            JournalEntry { bnb: None, ..settled(Side::Sell, 5, 0.0, 500.0) },
+        ];
+
+        // Average cost 0.2 per 1000: selling half for 0.5 realises 0.3
+        let trades = trade_pnl(&entries);
+        assert_eq!(trades.len(), 4);
+        assert_eq!(trades[0].1, None);
+        assert!((trades[2].1.unwrap() - 0.3).abs() < 1e-9);
+        assert_eq!(trades[3].1, None);
+
+        // 500 tokens left at a cost of 0.1
+        let position = journal::positions(&entries).remove(0);
+        let pnl = position_pnl(&position, Some(0.05));
+        assert!((pnl.realised - 0.3).abs() < 1e-9);
+        assert!((pnl.unrealised.unwrap() + 0.05).abs() < 1e-9);
+        assert_eq!(position_pnl(&position, None).unrealised, None);
+    }
+}
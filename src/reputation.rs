@@ -0,0 +1,228 @@
+use alloy::primitives::Address;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::env;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::Mutex;
+use anyhow::{Result, anyhow};
+
+use crate::SharedState;
+
+// Tokens remembered per creator, enough to count each launch once across overlapping monitors
+const RECENT_TOKENS: usize = 20;
+// Creators with no marks against them are forgotten after this long
+const FORGET_AFTER_SECS: i64 = 30 * 24 * 3600;
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CreatorRecord {
+    pub launches: u32,
+    // Launches where the creator sold off their allocation right after we bought
+    pub dumped: u32,
+    // Launches marked as rugs by hand
+    pub rugged: u32,
+    pub first_seen: i64,
+    pub last_seen: i64,
+    #[serde(default)]
+    pub tokens: Vec<Address>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Verdict {
+    Allowed,
+    Denied,
+    Score(u32),
+}
+
+// Local history of token creators, persisted as JSON next to the watchlist
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Reputation {
+    #[serde(default)]
+    pub allow: Vec<Address>,
+    #[serde(default)]
+    pub deny: Vec<Address>,
+    #[serde(default)]
+    pub creators: HashMap<Address, CreatorRecord>,
+    #[serde(skip)]
+    dirty: bool,
+}
+
+pub fn path() -> String {
+    env::var("REPUTATION_PATH").unwrap_or_else(|_| "reputation.json".to_string())
+}
+
+// Buys need at least this score; allow-listed creators always pass
+pub fn min_score() -> u32 {
+    env::var("MIN_CREATOR_SCORE").ok().and_then(|v| v.parse().ok()).unwrap_or(40)
+}
+
+// Launches within a day before the creator counts as a serial launcher
+fn serial_launches() -> u32 {
+    env::var("SERIAL_LAUNCH_LIMIT").ok().and_then(|v| v.parse().ok()).unwrap_or(3)
+}
+
+impl Reputation {
+    pub fn load(path: &str) -> Result<Self> {
+        match std::fs::read_to_string(path) {
+            Ok(raw) => Ok(serde_json::from_str(&raw)?),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    pub fn save(&mut self, path: &str) -> Result<()> {
+        let now = chrono::Utc::now().timestamp();
+        self.creators.retain(|_, r| r.dumped > 0 || r.rugged > 0 || now - r.last_seen < FORGET_AFTER_SECS);
+        std::fs::write(path, serde_json::to_string_pretty(self)?)?;
+        self.dirty = false;
+        Ok(())
+    }
+
+    // Launches are recorded far more often than anything else changes, so they are saved in batches
+    pub fn save_if_dirty(&mut self, path: &str) -> Result<()> {
+        if self.dirty {
+            self.save(path)?;
+        }
+        Ok(())
+    }
+
+    pub fn record_launch(&mut self, creator: Address, token: Address, now: i64) {
+        let record = self.creators.entry(creator).or_insert_with(|| CreatorRecord { first_seen: now, ..Default::default() });
+        if record.tokens.contains(&token) {
+            return;
+        }
+        record.launches += 1;
+        record.last_seen = now;
+        record.tokens.push(token);
+        if record.tokens.len() > RECENT_TOKENS {
+            record.tokens.remove(0);
+        }
+        self.dirty = true;
+    }
+
+    pub fn mark_dumped(&mut self, creator: Address) {
+        self.creators.entry(creator).or_default().dumped += 1;
+        self.dirty = true;
+    }
+
+    pub fn mark_rugged(&mut self, creator: Address) {
+        self.creators.entry(creator).or_default().rugged += 1;
+        self.dirty = true;
+    }
+
+    // Moves the creator onto one list (or neither), returning false if nothing changed
+    pub fn set_listing(&mut self, creator: Address, listing: Option<bool>) -> bool {
+        let before = (self.allow.contains(&creator), self.deny.contains(&creator));
+        self.allow.retain(|a| *a != creator);
+        self.deny.retain(|a| *a != creator);
+        match listing {
+            Some(true) => self.allow.push(creator),
+            Some(false) => self.deny.push(creator),
+            None => {}
+        }
+        let changed = before != (self.allow.contains(&creator), self.deny.contains(&creator));
+        self.dirty |= changed;
+        changed
+    }
+
+    // 0..=100: rugs weigh most, then immediate dumps, then launching many tokens in a short time
+    pub fn score(&self, creator: &Address, now: i64) -> u32 {
+        let Some(record) = self.creators.get(creator) else {
+            return 100;
+        };
+        let mut penalty = 40 * record.rugged as i64 + 25 * record.dumped as i64;
+        let days = ((now - record.first_seen).max(0) / 86_400 + 1) as u32;
+        let excess = record.launches.saturating_sub(serial_launches() * days);
+        penalty += 10 * excess as i64;
+        (100 - penalty).clamp(0, 100) as u32
+    }
+
+    pub fn verdict(&self, creator: &Address, now: i64) -> Verdict {
+        if self.deny.contains(creator) {
+            Verdict::Denied
+        } else if self.allow.contains(creator) {
+            Verdict::Allowed
+        } else {
+            Verdict::Score(self.score(creator, now))
+        }
+    }
+
+    // None when the creator may be bought from, otherwise why not
+    pub fn block_reason(&self, creator: &Address, now: i64) -> Option<String> {
+        match self.verdict(creator, now) {
+            Verdict::Allowed => None,
+            Verdict::Denied => Some("creator is on the deny list".to_string()),
+            Verdict::Score(score) if score < min_score() => Some(format!("creator score {} is below {}", score, min_score())),
+            Verdict::Score(_) => None,
+        }
+    }
+
+    pub fn describe(&self, creator: &Address) -> String {
+        let now = chrono::Utc::now().timestamp();
+        let verdict = match self.verdict(creator, now) {
+            Verdict::Allowed => "✅ allow-listed".to_string(),
+            Verdict::Denied => "⛔ deny-listed".to_string(),
+            Verdict::Score(score) => format!("score {}/100 (min {})", score, min_score()),
+        };
+        match self.creators.get(creator) {
+            Some(r) => format!(
+                "`{:?}` — {}\n{} launches | {} dumped | {} rugged | first seen <t:{}:R>",
+                creator, verdict, r.launches, r.dumped, r.rugged, r.first_seen
+            ),
+            None => format!("`{:?}` — {}\nNo launches seen yet", creator, verdict),
+        }
+    }
+}
+
+// Flushes recorded launches to disk every `interval`
+pub async fn run_saver(state: Arc<Mutex<SharedState>>, interval: Duration) {
+    loop {
+        tokio::time::sleep(interval).await;
+        if let Err(e) = state.lock().await.reputation.save_if_dirty(&path()) {
+            eprintln!("Failed to save creator reputation: {:?}", e);
+        }
+    }
+}
+
+pub fn parse_address(value: &str) -> Result<Address> {
+    value.parse().map_err(|_| anyhow!("'{}' is not an address", value))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_scores_and_lists() {
+        let mut rep = Reputation::default();
+        let creator = Address::repeat_byte(1);
+        let day = 86_400;
+        assert_eq!(rep.verdict(&creator, 0), Verdict::Score(100));
+
+        // The same launch seen by two monitors counts once
+        rep.record_launch(creator, Address::repeat_byte(10), 0);
+        rep.record_launch(creator, Address::repeat_byte(10), 0);
+        assert_eq!(rep.creators[&creator].launches, 1);
+
+        for i in 11..16 {
+            rep.record_launch(creator, Address::repeat_byte(i), 0);
+        }
+        // Six launches in a day, three over the limit
+        assert_eq!(rep.score(&creator, 0), 70);
+        // Spread over two days only the launches beyond six count
+        assert_eq!(rep.score(&creator, day), 100);
+
+        rep.mark_dumped(creator);
+        rep.mark_rugged(creator);
+        assert_eq!(rep.score(&creator, day), 35);
+        assert!(rep.block_reason(&creator, day).is_some());
+
+        assert!(rep.set_listing(creator, Some(true)));
+        assert_eq!(rep.verdict(&creator, day), Verdict::Allowed);
+        assert!(rep.set_listing(creator, Some(false)));
+        assert!(!rep.allow.contains(&creator));
+        assert_eq!(rep.verdict(&creator, day), Verdict::Denied);
+        assert!(rep.set_listing(creator, None));
+        assert!(!rep.set_listing(creator, None));
+    }
+}
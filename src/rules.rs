@@ -0,0 +1,385 @@
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::env;
+use anyhow::{Result, anyhow};
+
+use crate::keywords::{self, Keyword};
+use crate::tweet::{Tweet, TweetKind};
+
+// What kind of bot posts into a watched channel; decides how its messages are read
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FeedType {
+    // "New Tweet from @account" relays
+    TweetRelay,
+    // Telegram -> Discord bridges; the source channel is in the embed author or first line
+    TelegramBridge,
+    // Headline bots; the account only has to be mentioned somewhere
+    NewsBot,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct RuleSet {
+    pub accounts: Vec<String>,
+    pub keywords: Vec<Keyword>,
+    // Tweet kinds a relay trigger may come from; empty accepts all
+    #[serde(default)]
+    pub kinds: Vec<TweetKind>,
+}
+
+impl Default for RuleSet {
+    fn default() -> Self {
+        Self {
+            accounts: vec!["cz_binance".to_string(), "Scratch_XOX".to_string()],
+            keywords: vec!["book*".into(), "publish*".into(), "release*".into()],
+            kinds: Vec::new(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct WatchedChannel {
+    pub id: u64,
+    pub feed: FeedType,
+    #[serde(default = "default_rule_set")]
+    pub rules: String,
+    #[serde(default)]
+    pub label: Option<String>,
+}
+
+impl WatchedChannel {
+    pub fn name(&self) -> String {
+        self.label.clone().unwrap_or_else(|| self.id.to_string())
+    }
+}
+
+pub(crate) fn default_rule_set() -> String {
+    "default".to_string()
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct TriggerConfig {
+    pub channels: Vec<WatchedChannel>,
+    #[serde(default)]
+    pub rule_sets: HashMap<String, RuleSet>,
+    #[serde(default)]
+    pub notify_channel_id: Option<u64>,
+    // Direct tweet streams consumed alongside the Discord relays
+    #[serde(default)]
+    pub feeds: Vec<crate::feed::FeedConfig>,
+    // Telegram channels and groups read through the Bot API
+    #[serde(default)]
+    pub telegram: Option<crate::telegram::TelegramConfig>,
+}
+
+impl TriggerConfig {
+    // Reads TRIGGERS_CONFIG (JSON) if set, otherwise watches DISCORD_CHANNEL_ID as a tweet relay
+    pub fn from_env() -> Result<Self> {
+        let mut config = match env::var("TRIGGERS_CONFIG") {
+            Ok(path) => {
+                let raw = std::fs::read_to_string(&path)
+                    .map_err(|e| anyhow!("Failed to read TRIGGERS_CONFIG {}: {}", path, e))?;
+                serde_json::from_str::<TriggerConfig>(&raw)?
+            }
+            Err(_) => {
+                let channel_id: u64 = env::var("DISCORD_CHANNEL_ID")
+                    .map_err(|_| anyhow!("Expected TRIGGERS_CONFIG or DISCORD_CHANNEL_ID in environment"))?
+                    .parse()
+                    .map_err(|_| anyhow!("Channel ID must be a number"))?;
+                TriggerConfig {
+                    channels: vec![WatchedChannel {
+                        id: channel_id,
+                        feed: FeedType::TweetRelay,
+                        rules: default_rule_set(),
+                        label: None,
+                    }],
+                    rule_sets: HashMap::new(),
+                    notify_channel_id: None,
+                    feeds: Vec::new(),
+                    telegram: None,
+                }
+            }
+        };
+
+        if let Some(id) = env::var("DISCORD_NOTIFY_CHANNEL_ID").ok().and_then(|id| id.parse().ok()) {
+            config.notify_channel_id = Some(id);
+        }
+        config.validate()?;
+        Ok(config)
+    }
+
+    fn validate(&self) -> Result<()> {
+        if self.channels.is_empty() {
+            return Err(anyhow!("Trigger config has no watched channels"));
+        }
+        for channel in &self.channels {
+            if !self.has_rule_set(&channel.rules) {
+                return Err(anyhow!("Channel {} uses unknown rule set '{}'", channel.name(), channel.rules));
+            }
+        }
+        for feed in &self.feeds {
+            if !self.has_rule_set(&feed.rules) {
+                return Err(anyhow!("Feed {} uses unknown rule set '{}'", feed.name(), feed.rules));
+            }
+        }
+        for chat in self.telegram.iter().flat_map(|t| &t.chats) {
+            if !self.has_rule_set(&chat.rules) {
+                return Err(anyhow!("Telegram chat {} uses unknown rule set '{}'", chat.name(), chat.rules));
+            }
+        }
+        Ok(())
+    }
+
+    fn has_rule_set(&self, name: &str) -> bool {
+        name == default_rule_set() || self.rule_sets.contains_key(name)
+    }
+
+    pub fn channel(&self, id: u64) -> Option<&WatchedChannel> {
+        self.channels.iter().find(|c| c.id == id)
+    }
+
+    pub fn rules_for(&self, channel: &WatchedChannel) -> RuleSet {
+        self.rule_set(&channel.rules)
+    }
+
+    pub fn rule_set(&self, name: &str) -> RuleSet {
+        self.rule_sets.get(name).cloned().unwrap_or_default()
+    }
+
+    // Where status, trigger and trade messages go; defaults to the first watched channel
+    pub fn notify_channel(&self) -> u64 {
+        self.notify_channel_id.unwrap_or(self.channels[0].id)
+    }
+}
+
+// Platform-neutral view of a message, so matching does not depend on serenity types
+#[derive(Debug, Clone, Default)]
+pub struct MessageText {
+    pub content: String,
+    pub embeds: Vec<EmbedText>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct EmbedText {
+    pub author: Option<String>,
+    pub title: Option<String>,
+    pub description: Option<String>,
+    pub url: Option<String>,
+    pub author_url: Option<String>,
+    pub fields: Vec<(String, String)>,
+    pub footer: Option<String>,
+    pub image: Option<String>,
+    pub timestamp: Option<String>,
+}
+
+impl EmbedText {
+    pub fn from_discord(embed: &serenity::model::channel::Embed) -> Self {
+        Self {
+            author: embed.author.as_ref().map(|a| a.name.clone()),
+            title: embed.title.clone(),
+            description: embed.description.clone(),
+            url: embed.url.clone(),
+            author_url: embed.author.as_ref().and_then(|a| a.url.clone()),
+            fields: embed.fields.iter().map(|f| (f.name.clone(), f.value.clone())).collect(),
+            footer: embed.footer.as_ref().map(|f| f.text.clone()),
+            image: embed.image.as_ref().map(|i| i.url.clone()),
+            timestamp: embed.timestamp.map(|t| t.to_string()),
+        }
+    }
+}
+
+impl MessageText {
+    pub fn from_discord(msg: &serenity::model::channel::Message) -> Self {
+        Self {
+            content: msg.content.clone(),
+            embeds: msg.embeds.iter().map(EmbedText::from_discord).collect(),
+        }
+    }
+
+    // Short label for the terminal log
+    pub fn summary(&self) -> String {
+        let mut log_name = if !self.content.is_empty() {
+            self.content.clone()
+        } else if let Some(first_embed) = self.embeds.first() {
+            first_embed.title.clone().unwrap_or_else(|| "Unknown Embed".to_string())
+        } else {
+            "Empty Message".to_string()
+        };
+
+        // Truncate long content for cleaner terminal logs
+        if log_name.len() > 100 {
+            log_name = log_name.chars().take(97).collect();
+            log_name.push_str("...");
+        }
+        log_name
+    }
+
+    fn all_text(&self) -> String {
+        let mut text = self.content.clone();
+        for embed in &self.embeds {
+            for part in [&embed.author, &embed.title, &embed.description].into_iter().flatten() {
+                text.push('\n');
+                text.push_str(part);
+            }
+        }
+        text
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct TriggerMatch {
+    pub account: String,
+    pub keyword: String,
+}
+
+pub fn match_message(feed: FeedType, rules: &RuleSet, msg: &MessageText) -> Option<TriggerMatch> {
+    let (account, content_to_search) = match feed {
+        FeedType::TweetRelay => match_tweet_relay(rules, msg)?,
+        FeedType::TelegramBridge => {
+            let first_line = msg.content.lines().next().unwrap_or_default();
+            let mut sources: Vec<&str> = msg.embeds.iter().filter_map(|e| e.author.as_deref()).collect();
+            sources.push(first_line);
+            let account = find_account(rules, &sources)?;
+            (account, msg.all_text().to_lowercase())
+        }
+        FeedType::NewsBot => {
+            let text = msg.all_text();
+            let account = find_account(rules, &[text.as_str()])?;
+            (account, text.to_lowercase())
+        }
+    };
+
+    if !rules.kinds.is_empty() && !rules.kinds.contains(&Tweet::from_message(msg).kind) {
+        return None;
+    }
+
+    // Only trigger if account matched AND one of the keywords is found
+    let keyword = find_keyword(rules, &content_to_search)?;
+    Some(TriggerMatch { account, keyword })
+}
+
+// Direct feeds give the author handle explicitly, so it is compared exactly
+pub fn match_tweet(rules: &RuleSet, author: &str, text: &str) -> Option<TriggerMatch> {
+    let author = author.trim_start_matches('@');
+    let account = if rules.accounts.is_empty() {
+        String::new()
+    } else {
+        rules.accounts.iter().find(|a| a.eq_ignore_ascii_case(author))?.clone()
+    };
+    let keyword = find_keyword(rules, &text.to_lowercase())?;
+    Some(TriggerMatch { account, keyword })
+}
+
+fn find_keyword(rules: &RuleSet, content_to_search: &str) -> Option<String> {
+    keywords::find(&rules.keywords, content_to_search).map(|k| k.pattern.clone())
+}
+
+fn match_tweet_relay(rules: &RuleSet, msg: &MessageText) -> Option<(String, String)> {
+    let header = |account: &String| format!("New Tweet from @{}", account);
+
+    if let Some(account) = rules.accounts.iter().find(|a| msg.content == header(a)) {
+        let mut content_to_search = msg.content.to_lowercase();
+        for embed in &msg.embeds {
+            for part in [&embed.title, &embed.description].into_iter().flatten() {
+                content_to_search.push('\n');
+                content_to_search.push_str(&part.to_lowercase());
+            }
+        }
+        return Some((account.clone(), content_to_search));
+    }
+
+    // Check if any embed contains the trigger; the tweet body can sit in the other field
+    let mut matched = None;
+    let mut content_to_search = msg.content.to_lowercase();
+    for embed in &msg.embeds {
+        let parts: Vec<&String> = [&embed.title, &embed.description].into_iter().flatten().collect();
+        if let Some(account) = rules.accounts.iter().find(|a| parts.iter().any(|p| p.contains(&header(a)))) {
+            matched.get_or_insert_with(|| account.clone());
+            for part in parts {
+                content_to_search.push('\n');
+                content_to_search.push_str(&part.to_lowercase());
+            }
+        }
+    }
+    matched.map(|account| (account, content_to_search))
+}
+
+// An empty account list accepts any source
+fn find_account(rules: &RuleSet, sources: &[&str]) -> Option<String> {
+    if rules.accounts.is_empty() {
+        return Some(String::new());
+    }
+    rules.accounts.iter().find(|account| {
+        let account = account.to_lowercase();
+        sources.iter().any(|s| s.to_lowercase().contains(&account))
+    }).cloned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn embed(title: &str, description: &str) -> EmbedText {
+        EmbedText { title: Some(title.to_string()), description: Some(description.to_string()), ..Default::default() }
+    }
+
+    #[test]
+    fn test_tweet_relay_matching() {
+        let rules = RuleSet::default();
+        let msg = MessageText {
+            content: String::new(),
+            embeds: vec![embed("New Tweet from @cz_binance", "Going to publish my book soon")],
+        };
+        assert_eq!(
+            match_message(FeedType::TweetRelay, &rules, &msg),
+            Some(TriggerMatch { account: "cz_binance".to_string(), keyword: "book*".to_string() })
+        );
+
+        let other_account = MessageText {
+            content: String::new(),
+            embeds: vec![embed("New Tweet from @someone", "new book release")],
+        };
+        assert_eq!(match_message(FeedType::TweetRelay, &rules, &other_account), None);
+
+        let no_keyword = MessageText { content: "New Tweet from @cz_binance".to_string(), embeds: vec![embed("", "gm")] };
+        assert_eq!(match_message(FeedType::TweetRelay, &rules, &no_keyword), None);
+    }
+
+    #[test]
+    fn test_bridge_and_news_matching() {
+        let rules = RuleSet { accounts: vec!["CZ".to_string()], keywords: vec!["release".into()], kinds: vec![] };
+        let bridged = MessageText {
+            content: "CZ 🔶 BNB\nWe will release the book next week".to_string(),
+            embeds: vec![],
+        };
+        assert!(match_message(FeedType::TelegramBridge, &rules, &bridged).is_some());
+
+        // The bridge only trusts the source line, not mentions in the body
+        let mention = MessageText { content: "Random channel\nCZ said release".to_string(), embeds: vec![] };
+        assert!(match_message(FeedType::TelegramBridge, &rules, &mention).is_none());
+        assert!(match_message(FeedType::NewsBot, &rules, &mention).is_some());
+
+        let any_source = RuleSet { accounts: vec![], keywords: vec!["release".into()], kinds: vec![] };
+        assert!(match_message(FeedType::NewsBot, &any_source, &MessageText { content: "Binance to release".to_string(), embeds: vec![] }).is_some());
+    }
+
+    #[test]
+    fn test_config_parsing() {
+        let config: TriggerConfig = serde_json::from_str(r#"{
+            "channels": [
+                {"id": 1, "feed": "tweet_relay", "label": "relay-a"},
+                {"id": 2, "feed": "news_bot", "rules": "news"}
+            ],
+            "rule_sets": {"news": {"accounts": [], "keywords": ["listing"]}},
+            "notify_channel_id": 3
+        }"#).unwrap();
+        assert_eq!(config.channel(2).unwrap().feed, FeedType::NewsBot);
+        assert_eq!(config.rules_for(config.channel(2).unwrap()).keywords, vec![Keyword::from("listing")]);
+        assert_eq!(config.rules_for(config.channel(1).unwrap()).accounts, RuleSet::default().accounts);
+        assert_eq!(config.notify_channel(), 3);
+        assert!(config.validate().is_ok());
+
+        let unknown: TriggerConfig = serde_json::from_str(r#"{"channels": [{"id": 1, "feed": "news_bot", "rules": "missing"}]}"#).unwrap();
+        assert!(unknown.validate().is_err());
+    }
+}
@@ -0,0 +1,74 @@
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+use anyhow::Result;
+
+use crate::{metrics::METRICS, health::{self, HEALTH}};
+
+// Minimal HTTP/1.1 server for local scraping; one request per connection
+pub async fn serve(addr: &str) -> Result<()> {
+    let listener = TcpListener::bind(addr).await?;
+    let now = chrono::Local::now().format("%Y-%m-%d %H:%M:%S").to_string();
+    println!("[{}] 📈 HTTP endpoint listening on http://{} (/metrics, /health, /ready)", now, addr);
+
+    loop {
+        let (socket, _) = listener.accept().await?;
+        tokio::spawn(async move {
+            if let Err(e) = handle_connection(socket).await {
+                eprintln!("HTTP connection error: {:?}", e);
+            }
+        });
+    }
+}
+
+async fn handle_connection(mut socket: TcpStream) -> Result<()> {
+    let mut buf = vec![0u8; 4096];
+    let mut len = 0;
+    // Read until the end of the request headers; request bodies are ignored
+    while len < buf.len() {
+        let n = socket.read(&mut buf[len..]).await?;
+        if n == 0 {
+            break;
+        }
+        len += n;
+        if buf[..len].windows(4).any(|w| w == b"\r\n\r\n") {
+            break;
+        }
+    }
+
+    let request = String::from_utf8_lossy(&buf[..len]);
+    let mut parts = request.lines().next().unwrap_or_default().split_whitespace();
+    let method = parts.next().unwrap_or_default();
+    let path = parts.next().unwrap_or_default();
+
+    let (status, content_type, body) = route(method, path);
+    let response = format!(
+        "HTTP/1.1 {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        status, content_type, body.len(), body
+    );
+    socket.write_all(response.as_bytes()).await?;
+    socket.shutdown().await?;
+    Ok(())
+}
+
+fn route(method: &str, path: &str) -> (&'static str, &'static str, String) {
+    if method != "GET" {
+        return ("405 Method Not Allowed", "text/plain", "method not allowed\n".to_string());
+    }
+    match path {
+        "/metrics" => ("200 OK", "text/plain; version=0.0.4", METRICS.render()),
+        "/health" => {
+            let report = HEALTH.report(&health::Thresholds::from_env());
+            let status = if report.healthy { "200 OK" } else { "503 Service Unavailable" };
+            (status, "application/json", serde_json::to_string_pretty(&report).unwrap_or_default())
+        }
+        "/ready" => {
+            let report = HEALTH.report(&health::Thresholds::from_env());
+            if report.ready {
+                ("200 OK", "text/plain", "ready\n".to_string())
+            } else {
+                ("503 Service Unavailable", "text/plain", "not ready\n".to_string())
+            }
+        }
+        _ => ("404 Not Found", "text/plain", "not found\n".to_string()),
+    }
+}
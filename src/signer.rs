@@ -0,0 +1,259 @@
+use alloy::{
+    primitives::{Address, TxKind},
+    signers::local::PrivateKeySigner,
+    network::TxSigner,
+    consensus::{TxLegacy, TxEnvelope, SignableTransaction},
+    eips::eip2718::{Decodable2718, Encodable2718},
+};
+use async_trait::async_trait;
+use serde_json::{json, Value};
+use std::env;
+use std::path::PathBuf;
+use std::time::Duration;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use anyhow::{Result, anyhow};
+
+use crate::metrics::METRICS;
+
+// Signs the swap path's transactions; the key may live in this process or behind a socket
+#[async_trait]
+pub trait TransactionSigner: Send + Sync {
+    fn address(&self) -> Address;
+
+    // The EIP-2718 encoding of the signed transaction, ready for eth_sendRawTransaction
+    async fn sign_legacy(&self, tx: TxLegacy) -> Result<Vec<u8>>;
+
+    fn describe(&self) -> String;
+}
+
+pub struct LocalKey(pub PrivateKeySigner);
+
+#[async_trait]
+impl TransactionSigner for LocalKey {
+    fn address(&self) -> Address {
+        self.0.address()
+    }
+
+    async fn sign_legacy(&self, mut tx: TxLegacy) -> Result<Vec<u8>> {
+        let signature = self.0.sign_transaction(&mut tx).await?;
+        Ok(TxEnvelope::Legacy(tx.into_signed(signature)).encoded_2718())
+    }
+
+    fn describe(&self) -> String {
+        "local key".to_string()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Endpoint {
+    Http(String),
+    Unix(PathBuf),
+}
+
+impl Endpoint {
+    // http(s):// URLs, unix:/path, or a bare socket path
+    pub fn parse(url: &str) -> Self {
+        if url.starts_with("http://") || url.starts_with("https://") {
+            return Endpoint::Http(url.to_string());
+        }
+        let path = url.strip_prefix("unix://").or_else(|| url.strip_prefix("unix:")).unwrap_or(url);
+        Endpoint::Unix(PathBuf::from(path))
+    }
+}
+
+// An external signer process answering eth_signTransaction (geth/clef style) over
+// HTTP JSON-RPC or newline-delimited JSON-RPC on a Unix socket
+pub struct RemoteSigner {
+    pub address: Address,
+    pub endpoint: Endpoint,
+    timeout: Duration,
+}
+
+impl RemoteSigner {
+    pub fn new(address: Address, endpoint: Endpoint) -> Self {
+        let timeout = Duration::from_millis(env::var("SIGNER_TIMEOUT_MS").ok().and_then(|v| v.parse().ok()).unwrap_or(5_000));
+        RemoteSigner { address, endpoint, timeout }
+    }
+
+    async fn call(&self, request: Value) -> Result<Value> {
+        let response = match &self.endpoint {
+            Endpoint::Http(url) => {
+                reqwest::Client::new().post(url).json(&request).send().await?.error_for_status()?.json::<Value>().await?
+            }
+            Endpoint::Unix(path) => {
+                let mut stream = tokio::net::UnixStream::connect(path).await
+                    .map_err(|e| anyhow!("Failed to connect to signer at {}: {}", path.display(), e))?;
+                stream.write_all(format!("{}\n", request).as_bytes()).await?;
+                // Read until a whole JSON value has arrived
+                let mut buf = Vec::new();
+                let mut chunk = [0u8; 4096];
+                loop {
+                    let n = stream.read(&mut chunk).await?;
+                    buf.extend_from_slice(&chunk[..n]);
+                    if let Ok(value) = serde_json::from_slice::<Value>(&buf) {
+                        break value;
+                    }
+                    if n == 0 {
+                        return Err(anyhow!("Signer closed the connection mid-response"));
+                    }
+                }
+            }
+        };
+        if let Some(error) = response.get("error") {
+            return Err(anyhow!("Signer refused: {}", error));
+        }
+        response.get("result").cloned().ok_or_else(|| anyhow!("Signer response has no result"))
+    }
+}
+
+#[async_trait]
+impl TransactionSigner for RemoteSigner {
+    fn address(&self) -> Address {
+        self.address
+    }
+
+    async fn sign_legacy(&self, tx: TxLegacy) -> Result<Vec<u8>> {
+        let to = match tx.to {
+            TxKind::Call(to) => Some(to),
+            TxKind::Create => None,
+        };
+        let request = json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "method": "eth_signTransaction",
+            "params": [{
+                "from": self.address,
+                "to": to,
+                "gas": format!("0x{:x}", tx.gas_limit),
+                "gasPrice": format!("0x{:x}", tx.gas_price),
+                "value": format!("0x{:x}", tx.value),
+                "data": format!("0x{}", hex::encode(&tx.input)),
+                "nonce": format!("0x{:x}", tx.nonce),
+                "chainId": tx.chain_id.map(|id| format!("0x{:x}", id)),
+            }],
+        });
+        let result = tokio::time::timeout(self.timeout, self.call(request)).await
+            .map_err(|_| anyhow!("Signer timed out after {:?}", self.timeout))?
+            .inspect_err(|_| METRICS.rpc_error("eth_signTransaction"))?;
+
+        // geth and clef answer {raw, tx}; others return the raw hex alone
+        let raw = result.get("raw").unwrap_or(&result).as_str()
+            .ok_or_else(|| anyhow!("Signer returned no raw transaction"))?;
+        let raw = hex::decode(raw.trim_start_matches("0x"))?;
+        verify(&raw, &tx, self.address)?;
+        Ok(raw)
+    }
+
+    fn describe(&self) -> String {
+        match &self.endpoint {
+            Endpoint::Http(url) => format!("remote signer at {}", url),
+            Endpoint::Unix(path) => format!("remote signer at {}", path.display()),
+        }
+    }
+}
+
+// A remote signer must sign exactly what was asked, with the expected key
+fn verify(raw: &[u8], expected: &TxLegacy, address: Address) -> Result<()> {
+    let envelope = TxEnvelope::decode_2718(&mut &raw[..]).map_err(|e| anyhow!("Signer returned an undecodable transaction: {}", e))?;
+    let signed = envelope.as_legacy().ok_or_else(|| anyhow!("Signer returned a non-legacy transaction"))?;
+    if signed.tx() != expected {
+        return Err(anyhow!("Signer altered the transaction"));
+    }
+    let signer = signed.recover_signer()?;
+    if signer != address {
+        return Err(anyhow!("Transaction signed by {:?}, expected {:?}", signer, address));
+    }
+    Ok(())
+}
+
+// SIGNER_URL with one wallet per SIGNER_ADDRESS (comma separated); None when unset
+pub fn remote_from_env() -> Result<Option<Vec<Box<dyn TransactionSigner>>>> {
+    let Ok(url) = env::var("SIGNER_URL") else {
+        return Ok(None);
+    };
+    let addresses = env::var("SIGNER_ADDRESS").map_err(|_| anyhow!("SIGNER_ADDRESS is required with SIGNER_URL"))?;
+    let endpoint = Endpoint::parse(&url);
+    let mut signers: Vec<Box<dyn TransactionSigner>> = Vec::new();
+    for address in addresses.split(',').map(str::trim).filter(|a| !a.is_empty()) {
+        signers.push(Box::new(RemoteSigner::new(address.parse()?, endpoint.clone())));
+    }
+    Ok(Some(signers))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy::primitives::{Bytes, U256};
+    use alloy::network::TxSignerSync;
+    use tokio::io::{AsyncBufReadExt, BufReader};
+
+    // Reference signer: answers eth_signTransaction for one key, the way a locked-down signer process would
+    fn mock_sign(key: &PrivateKeySigner, request: &Value) -> Value {
+        let params = &request["params"][0];
+        let quantity = |field: &str| u128::from_str_radix(params[field].as_str().unwrap().trim_start_matches("0x"), 16).unwrap();
+        if params["from"].as_str().unwrap().parse::<Address>().unwrap() != key.address() {
+            return json!({"jsonrpc": "2.0", "id": request["id"], "error": {"code": -32000, "message": "unknown account"}});
+        }
+        let mut tx = TxLegacy {
+            chain_id: Some(quantity("chainId") as u64),
+            nonce: quantity("nonce") as u64,
+            gas_price: quantity("gasPrice"),
+            gas_limit: quantity("gas"),
+            to: TxKind::Call(params["to"].as_str().unwrap().parse().unwrap()),
+            value: U256::from(quantity("value")),
+            input: hex::decode(params["data"].as_str().unwrap().trim_start_matches("0x")).unwrap().into(),
+        };
+        let signature = key.sign_transaction_sync(&mut tx).unwrap();
+        let raw = TxEnvelope::Legacy(tx.into_signed(signature)).encoded_2718();
+        json!({"jsonrpc": "2.0", "id": request["id"], "result": {"raw": format!("0x{}", hex::encode(raw)), "tx": {}}})
+    }
+
+    fn legacy_tx() -> TxLegacy {
+        TxLegacy {
+            chain_id: Some(56),
+            nonce: 7,
+            gas_price: 80_000_000_000,
+            gas_limit: 500_000,
+            to: TxKind::Call(Address::repeat_byte(0x5c)),
+            value: U256::from(10_000_000_000_000_000u64),
+            input: Bytes::from(vec![0xde, 0xad, 0xbe, 0xef]),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_remote_signer_over_unix_socket_and_http() {
+        let key = PrivateKeySigner::random();
+        let local = LocalKey(key.clone()).sign_legacy(legacy_tx()).await.unwrap();
+
+        let socket = env::temp_dir().join(format!("czsniper-signer-{}.sock", std::process::id()));
+        let _ = std::fs::remove_file(&socket);
+        let listener = tokio::net::UnixListener::bind(&socket).unwrap();
+        let unix_key = key.clone();
+        tokio::spawn(async move {
+            while let Ok((conn, _)) = listener.accept().await {
+                let (read, mut write) = conn.into_split();
+                let mut line = String::new();
+                BufReader::new(read).read_line(&mut line).await.unwrap();
+                let response = mock_sign(&unix_key, &serde_json::from_str(&line).unwrap());
+                write.write_all(format!("{}\n", response).as_bytes()).await.unwrap();
+            }
+        });
+
+        let http_key = key.clone();
+        let url = crate::testutil::mock_http(move |request| mock_sign(&http_key, &serde_json::from_str(&request.body).unwrap()).to_string()).await;
+
+        for endpoint in [Endpoint::parse(&format!("unix:{}", socket.display())), Endpoint::parse(&url)] {
+            let remote = RemoteSigner::new(key.address(), endpoint.clone());
+            assert_eq!(remote.sign_legacy(legacy_tx()).await.unwrap(), local, "{:?}", endpoint);
+
+            // The signer does not hold the key for another account
+            let stranger = RemoteSigner::new(Address::repeat_byte(0x01), endpoint);
+            assert!(stranger.sign_legacy(legacy_tx()).await.unwrap_err().to_string().contains("unknown account"));
+        }
+
+        // Signed by the right key but not what was asked for
+        assert!(verify(&local, &TxLegacy { nonce: 8, ..legacy_tx() }, key.address()).is_err());
+        assert!(verify(&local, &legacy_tx(), Address::repeat_byte(0x01)).is_err());
+        std::fs::remove_file(&socket).unwrap();
+    }
+}
@@ -1,16 +1,25 @@
 use alloy::{
     primitives::{Address, U256, B256, Bytes, TxKind},
     providers::{Provider, ProviderBuilder},
-    signers::local::PrivateKeySigner,
-    network::{EthereumWallet, TxSigner},
-    consensus::{TxLegacy, TxEnvelope, SignableTransaction},
-    eips::eip2718::Encodable2718,
+    transports::Transport,
+    consensus::TxLegacy,
+    rpc::types::eth::TransactionReceipt,
     sol,
+    sol_types::SolCall,
 };
 use serde_json::json;
 use std::env;
 use std::str::FromStr;
-use anyhow::Result;
+use std::time::{Duration, Instant};
+use anyhow::{Result, anyhow};
+
+use crate::metrics::METRICS;
+use crate::journal::{self, JournalEntry, Side, Status};
+use crate::metrics;
+use crate::honeypot::{self, PancakeRouter, WBNB};
+use crate::migration::{self, Venue};
+use crate::signer::TransactionSigner;
+use crate::wallets;
 
 sol!(
     #[sol(rpc)]
@@ -18,6 +27,10 @@ sol!(
         function buyTokenAMAP(address token, uint256 funds, uint256 minAmount)
             external
             payable;
+
+        function sellToken(address token, uint256 amount) external;
+
+        event TokenSale(address token, address account, uint256 price, uint256 amount, uint256 cost, uint256 fee, uint256 offers, uint256 funds);
         
         // Keeping the old one just in case, or for reference
         struct SwapDesc {
@@ -39,24 +52,35 @@ sol!(
     }
 );
 
+sol!(
+    #[sol(rpc)]
+    contract IERC20 {
+        function balanceOf(address owner) external view returns (uint256);
+        function allowance(address owner, address spender) external view returns (uint256);
+        function approve(address spender, uint256 amount) external returns (bool);
+
+        event Transfer(address indexed from, address indexed to, uint256 value);
+    }
+);
+
+sol!(
+    contract IWBNB {
+        // Emitted when the PancakeSwap router unwraps a sell's proceeds
+        event Withdrawal(address indexed src, uint256 wad);
+    }
+);
+
 pub async fn simulate_swap(token_address_str: &str) -> Result<()> {
     let rpc_url = env::var("BSC_RPC_URL")?;
-    let private_key = env::var("PRIVATE_KEY")?;
     let buy_amount_bnb = env::var("BUY_AMOUNT_BNB")?.parse::<f64>()?;
     let router_address_str = env::var("PANCAKE_ROUTER")?;
     let router_address = Address::from_str(&router_address_str)?;
     let token_address = Address::from_str(token_address_str)?;
 
-    let signer: PrivateKeySigner = private_key.parse()?;
-    let wallet = EthereumWallet::from(signer.clone());
-
-    let provider = ProviderBuilder::new()
-        .with_recommended_fillers()
-        .wallet(wallet)
-        .on_builtin(&rpc_url)
-        .await?;
+    let from = wallet_address()?;
+    let provider = ProviderBuilder::new().on_builtin(&rpc_url).await?;
 
-    let funds = U256::from((buy_amount_bnb * 1e18) as u64);
+    let funds = to_wei(buy_amount_bnb)?;
     
     println!("Simulating buyTokenAMAP for {} BNB of token {}", buy_amount_bnb, token_address);
 
@@ -70,13 +94,13 @@ pub async fn simulate_swap(token_address_str: &str) -> Result<()> {
 
     let calldata = format!("0x{}", hex::encode(call.calldata()));
     println!("Raw Calldata: {}", calldata);
-    println!("From Address: {:?}", signer.address());
+    println!("From Address: {:?}", from);
     println!("Value:        {} wei", funds);
 
     // Raw JSON-RPC eth_call
     let params = json!([
         {
-            "from": signer.address(),
+            "from": from,
             "to": router_address,
             "value": format!("0x{:x}", funds),
             "data": calldata
@@ -95,64 +119,439 @@ pub async fn simulate_swap(token_address_str: &str) -> Result<()> {
     Ok(())
 }
 
-pub async fn execute_swap(token_address_str: &str) -> Result<()> {
-    let rpc_url = env::var("BSC_RPC_URL")?;
-    let private_key = env::var("PRIVATE_KEY")?;
-    let buy_amount_bnb = env::var("BUY_AMOUNT_BNB")?.parse::<f64>()?;
-    let router_address_str = env::var("PANCAKE_ROUTER")?;
-    let router_address = Address::from_str(&router_address_str)?;
-    let token_address = Address::from_str(token_address_str)?;
+pub struct SwapOutcome {
+    pub wallet: Address,
+    pub tx_hash: B256,
+    pub funds: U256,
+    pub started_at: Instant,
+    pub signed_at: Instant,
+    pub broadcast_at: Instant,
+}
 
-    let signer: PrivateKeySigner = private_key.parse()?;
-    let wallet = EthereumWallet::from(signer.clone());
+pub struct SellOutcome {
+    pub wallet: Address,
+    pub tx_hash: B256,
+    pub amount: U256,
+}
 
-    let provider = ProviderBuilder::new()
-        .with_recommended_fillers()
-        .wallet(wallet)
-        .on_builtin(&rpc_url)
-        .await?;
+// What a mined trade actually did, read back from its receipt
+#[derive(Debug, Clone)]
+pub struct Fill {
+    pub success: bool,
+    pub tokens: U256,
+    // BNB received on a sell: the curve's TokenSale cost, or the WBNB a PancakeSwap sell unwrapped
+    pub bnb_received: Option<U256>,
+    pub gas_paid: U256,
+    pub block_number: Option<u64>,
+}
 
-    let funds = U256::from((buy_amount_bnb * 1e18) as u64);
-    
-    println!("Preparing raw swap transaction for {} BNB of token {}", buy_amount_bnb, token_address);
+pub fn parse_fill(receipt: &TransactionReceipt, side: Side, token: Address, wallet: Address) -> Fill {
+    let mut tokens = U256::ZERO;
+    let mut bnb_received = None;
+    for log in receipt.inner.logs() {
+        if log.address() == token {
+            if let Ok(transfer) = log.log_decode::<IERC20::Transfer>() {
+                let transfer = transfer.inner.data;
+                match side {
+                    Side::Buy if transfer.to == wallet => tokens += transfer.value,
+                    Side::Sell if transfer.from == wallet => tokens += transfer.value,
+                    _ => {}
+                }
+            }
+        } else if log.address() == WBNB {
+            if let Ok(withdrawal) = log.log_decode::<IWBNB::Withdrawal>() {
+                if side == Side::Sell {
+                    bnb_received = Some(bnb_received.unwrap_or(U256::ZERO) + withdrawal.inner.data.wad);
+                }
+            }
+        } else if let Ok(sale) = log.log_decode::<FourMemeRouter::TokenSale>() {
+            let sale = sale.inner.data;
+            if side == Side::Sell && sale.token == token && sale.account == wallet {
+                bnb_received = Some(bnb_received.unwrap_or(U256::ZERO) + sale.cost);
+            }
+        }
+    }
 
-    let router = FourMemeRouter::new(router_address, provider.clone());
-    let call = router.buyTokenAMAP(token_address, funds, U256::ZERO);
-    let calldata = call.calldata().to_vec();
+    Fill {
+        success: receipt.status(),
+        tokens,
+        bnb_received,
+        gas_paid: U256::from(receipt.gas_used) * U256::from(receipt.effective_gas_price),
+        block_number: receipt.block_number,
+    }
+}
+
+pub fn to_units(value: U256) -> f64 {
+    value.to_string().parse::<f64>().unwrap_or_default() / 1e18
+}
+
+// BNB to wei without the u64 cap of an `as` cast
+pub fn to_wei(bnb: f64) -> Result<U256> {
+    Ok(alloy::primitives::utils::parse_ether(&format!("{:.18}", bnb))?)
+}
+
+struct SentTx {
+    tx_hash: B256,
+    signed_at: Instant,
+    broadcast_at: Instant,
+}
 
-    // 1. Get Nonce
-    let nonce = provider.get_transaction_count(signer.address()).await?;
+pub fn wallet_address() -> Result<Address> {
+    Ok(wallets::wallets()?.primary().address())
+}
+
+// Held across every wallet in the set
+pub async fn token_balance(token_address_str: &str) -> Result<U256> {
+    let token = Address::from_str(token_address_str)?;
+    let mut total = U256::ZERO;
+    for wallet in wallets::wallets()?.addresses() {
+        total += balance_of(token, wallet).await?;
+    }
+    Ok(total)
+}
+
+pub async fn balance_of(token: Address, owner: Address) -> Result<U256> {
+    let rpc_url = env::var("BSC_RPC_URL")?;
+    let provider = ProviderBuilder::new().on_builtin(&rpc_url).await?;
+    let token = IERC20::new(token, provider);
+    Ok(token.balanceOf(owner).call().await?._0)
+}
 
-    // 2. Get Gas Price
+// Builds, signs and broadcasts a raw legacy transaction (simplest for BSC)
+async fn send_legacy<P, T>(
+    provider: &P,
+    signer: &dyn TransactionSigner,
+    nonce: u64,
+    to: Address,
+    value: U256,
+    input: Vec<u8>,
+    gas_limit: u128,
+) -> Result<SentTx>
+where
+    P: Provider<T>,
+    T: Transport + Clone,
+{
     let gas_price = 80_000_000_000u128; // Fixed 80 Gwei
-    
-    // 3. Construct a raw Legacy Transaction (simplest for BSC)
-    let mut tx = TxLegacy {
+
+    let tx = TxLegacy {
         chain_id: Some(56), // BSC Mainnet
         nonce,
         gas_price,
-        gas_limit: 500_000,
-        to: TxKind::Call(router_address),
-        value: funds,
-        input: Bytes::from(calldata),
+        gas_limit,
+        to: TxKind::Call(to),
+        value,
+        input: Bytes::from(input),
     };
 
-    println!("Signing raw transaction...");
-    // 4. Sign the transaction using the PrivateKeySigner directly
-    let signature = signer.sign_transaction(&mut tx).await?;
-    
-    // 5. Create the signed envelope
-    let signed_tx = tx.into_signed(signature);
-    let envelope = TxEnvelope::Legacy(signed_tx);
-    let signed_tx_hex = format!("0x{}", hex::encode(envelope.encoded_2718()));
+    println!("Signing raw transaction with {}...", signer.describe());
+    let signed_tx_hex = format!("0x{}", hex::encode(signer.sign_legacy(tx).await?));
+    let signed_at = Instant::now();
 
     println!("Broadcasting raw transaction...");
 
-    // 6. Broadcast via raw JSON-RPC
-    match provider.raw_request::<_, B256>("eth_sendRawTransaction".into(), vec![signed_tx_hex]).await {
-        Ok(tx_hash) => println!("Transaction Sent! Hash: {:?}", tx_hash),
-        Err(e) => eprintln!("Failed to broadcast: {:?}", e),
+    // Broadcast via raw JSON-RPC
+    let tx_hash = provider.raw_request::<_, B256>("eth_sendRawTransaction".into(), vec![signed_tx_hex]).await
+        .map_err(|e| {
+            METRICS.rpc_error("eth_sendRawTransaction");
+            anyhow!("Failed to broadcast: {:?}", e)
+        })?;
+    let broadcast_at = Instant::now();
+    println!("Transaction Sent! Hash: {:?}", tx_hash);
+
+    Ok(SentTx { tx_hash, signed_at, broadcast_at })
+}
+
+// Sends one buy per wallet the strategy picks, each from its own nonce stream. Legs that
+// fail are logged; the call only fails if none went out.
+async fn spread_buy<F>(token_address_str: &str, buy_amount_bnb: f64, venue: &str, build: F) -> Result<Vec<SwapOutcome>>
+where
+    F: Fn(Address, U256) -> (Address, Vec<u8>),
+{
+    let started_at = Instant::now();
+    if buy_amount_bnb <= 0.0 {
+        return Err(anyhow!("Buy amount must be positive, got {} BNB", buy_amount_bnb));
     }
+    let rpc_url = env::var("BSC_RPC_URL")?;
+    let provider = ProviderBuilder::new().on_builtin(&rpc_url).await?;
+    let plan = wallets::wallets()?.plan_buy(buy_amount_bnb);
 
-    Ok(())
+    let plan = plan.into_iter()
+        .map(|(wallet, amount)| Ok((wallet, amount, to_wei(amount)?)))
+        .collect::<Result<Vec<_>>>()?;
+
+    let legs = plan.into_iter().map(|(wallet, amount, funds)| {
+        let provider = &provider;
+        let (to, calldata) = build(wallet.address(), funds);
+        async move {
+            println!("Preparing raw {} swap transaction for {} BNB of token {} from {:?}", venue, amount, token_address_str, wallet.address());
+            let nonce = wallet.nonces.reserve(provider, wallet.address()).await?;
+            let sent = match send_legacy(provider, wallet.signer.as_ref(), nonce, to, funds, calldata, 500_000).await {
+                Ok(sent) => sent,
+                Err(e) => {
+                    wallet.nonces.reset().await;
+                    return Err(e);
+                }
+            };
+            journal::record(JournalEntry::new(Side::Buy, token_address_str, sent.tx_hash, Some(amount), None).with_wallet(wallet.address()));
+            Ok::<_, anyhow::Error>(SwapOutcome {
+                wallet: wallet.address(),
+                tx_hash: sent.tx_hash,
+                funds,
+                started_at,
+                signed_at: sent.signed_at,
+                broadcast_at: sent.broadcast_at,
+            })
+        }
+    });
+
+    let mut outcomes = Vec::new();
+    let mut last_error = None;
+    for result in futures_util::future::join_all(legs).await {
+        match result {
+            Ok(outcome) => outcomes.push(outcome),
+            Err(e) => {
+                eprintln!("Buy leg failed for {}: {:?}", token_address_str, e);
+                last_error = Some(e);
+            }
+        }
+    }
+    match last_error {
+        Some(e) if outcomes.is_empty() => Err(e),
+        _ => Ok(outcomes),
+    }
+}
+
+pub async fn execute_swap(token_address_str: &str, buy_amount_bnb: f64) -> Result<Vec<SwapOutcome>> {
+    let router_address = Address::from_str(&env::var("PANCAKE_ROUTER")?)?;
+    let token_address = Address::from_str(token_address_str)?;
+    spread_buy(token_address_str, buy_amount_bnb, "four.meme", |_, funds| {
+        let call = FourMemeRouter::buyTokenAMAPCall { token: token_address, funds, minAmount: U256::ZERO };
+        (router_address, call.abi_encode())
+    }).await
+}
+
+// Pancake swaps only need to land in the next few blocks
+fn deadline() -> U256 {
+    U256::from(chrono::Utc::now().timestamp() + 60)
+}
+
+// Buys a token that has graduated to PancakeSwap V2
+pub async fn execute_pancake_buy(token_address_str: &str, buy_amount_bnb: f64) -> Result<Vec<SwapOutcome>> {
+    let router_address = honeypot::router()?;
+    let token_address = Address::from_str(token_address_str)?;
+    spread_buy(token_address_str, buy_amount_bnb, "PancakeSwap", |wallet, _| {
+        let call = PancakeRouter::swapExactETHForTokensSupportingFeeOnTransferTokensCall {
+            amountOutMin: U256::ZERO,
+            path: vec![WBNB, token_address],
+            to: wallet,
+            deadline: deadline(),
+        };
+        (router_address, call.abi_encode())
+    }).await
+}
+
+// Sells `percent` of each wallet's balance of a token, into the four.meme bonding curve or
+// through PancakeSwap once the token has migrated
+pub async fn execute_sell(token_address_str: &str, percent: u64) -> Result<Vec<SellOutcome>> {
+    if percent == 0 || percent > 100 {
+        return Err(anyhow!("Sell percentage must be between 1 and 100, got {}", percent));
+    }
+    let rpc_url = env::var("BSC_RPC_URL")?;
+    let token_address = Address::from_str(token_address_str)?;
+    let provider = ProviderBuilder::new().on_builtin(&rpc_url).await?;
+
+    let venue = migration::venue(token_address).await?;
+    let router_address = match venue {
+        Venue::Curve => Address::from_str(&env::var("PANCAKE_ROUTER")?)?,
+        Venue::Pancake => honeypot::router()?,
+    };
+    let token = IERC20::new(token_address, provider.clone());
+
+    // A failing wallet must not hide the sells that already went out from the others
+    let mut outcomes = Vec::new();
+    let mut last_error = None;
+    for wallet in &wallets::wallets()?.wallets {
+        let result = async {
+            let balance = token.balanceOf(wallet.address()).call().await?._0;
+            // four.meme only accepts token amounts rounded to 1e9 units
+            let granularity = U256::from(1_000_000_000u64);
+            let amount = balance * U256::from(percent) / U256::from(100) / granularity * granularity;
+            if amount.is_zero() {
+                return Ok(None);
+            }
+
+            println!("Preparing {:?} sell of {}% ({} units) of token {} from {:?}", venue, percent, amount, token_address, wallet.address());
+
+            // Approve first if needed; the sell takes the next nonce so it is ordered after the approval
+            let allowance = token.allowance(wallet.address(), router_address).call().await?._0;
+            if allowance < amount {
+                let approve = token.approve(router_address, U256::MAX).calldata().to_vec();
+                let nonce = wallet.nonces.reserve(&provider, wallet.address()).await?;
+                send_legacy(&provider, wallet.signer.as_ref(), nonce, token_address, U256::ZERO, approve, 100_000).await?;
+            }
+            let calldata = match venue {
+                Venue::Curve => FourMemeRouter::sellTokenCall { token: token_address, amount }.abi_encode(),
+                Venue::Pancake => PancakeRouter::swapExactTokensForETHSupportingFeeOnTransferTokensCall {
+                    amountIn: amount,
+                    amountOutMin: U256::ZERO,
+                    path: vec![token_address, WBNB],
+                    to: wallet.address(),
+                    deadline: deadline(),
+                }.abi_encode(),
+            };
+            let nonce = wallet.nonces.reserve(&provider, wallet.address()).await?;
+            let sent = send_legacy(&provider, wallet.signer.as_ref(), nonce, router_address, U256::ZERO, calldata, 500_000).await?;
+            Ok::<_, anyhow::Error>(Some((sent, amount)))
+        }.await;
+
+        match result {
+            Ok(Some((sent, amount))) => {
+                journal::record(JournalEntry::new(Side::Sell, token_address_str, sent.tx_hash, None, Some(to_units(amount))).with_wallet(wallet.address()));
+                outcomes.push(SellOutcome { wallet: wallet.address(), tx_hash: sent.tx_hash, amount });
+            }
+            Ok(None) => {}
+            Err(e) => {
+                wallet.nonces.reset().await;
+                eprintln!("Sell from {:?} failed for {}: {:?}", wallet.address(), token_address_str, e);
+                last_error = Some(e);
+            }
+        }
+    }
+
+    match last_error {
+        Some(e) if outcomes.is_empty() => Err(e),
+        _ if outcomes.is_empty() => Err(anyhow!("No balance of {} to sell", token_address)),
+        _ => Ok(outcomes),
+    }
+}
+
+// Polls for the receipt of a broadcast transaction; Ok(None) if it did not land in time
+pub async fn wait_for_receipt(tx_hash: B256, timeout: Duration) -> Result<Option<TransactionReceipt>> {
+    let rpc_url = env::var("BSC_RPC_URL")?;
+    let provider = ProviderBuilder::new().on_builtin(&rpc_url).await?;
+
+    let start = Instant::now();
+    while start.elapsed() < timeout {
+        match provider.get_transaction_receipt(tx_hash).await {
+            Ok(Some(receipt)) => return Ok(Some(receipt)),
+            Ok(None) => {}
+            Err(e) => {
+                METRICS.rpc_error("eth_getTransactionReceipt");
+                eprintln!("Receipt lookup failed for {:?}: {:?}", tx_hash, e);
+            }
+        }
+        tokio::time::sleep(Duration::from_millis(500)).await;
+    }
+
+    Ok(None)
+}
+
+// Waits for a trade to be mined, then records the fill in the journal and metrics
+pub async fn settle(tx_hash: B256, side: Side, token_address_str: &str, bnb_spent: Option<f64>, wallet: Option<Address>) -> Result<Option<Fill>> {
+    let Some(receipt) = wait_for_receipt(tx_hash, Duration::from_secs(60)).await? else {
+        return Ok(None);
+    };
+    let wallet = match wallet {
+        Some(wallet) => wallet,
+        None => wallet_address()?,
+    };
+    let fill = parse_fill(&receipt, side, Address::from_str(token_address_str)?, wallet);
+
+    if side == Side::Buy {
+        if fill.success {
+            metrics::inc(&METRICS.buys_succeeded);
+        } else {
+            metrics::inc(&METRICS.buys_reverted);
+        }
+    }
+
+    let bnb = match side {
+        Side::Buy => bnb_spent,
+        Side::Sell => fill.bnb_received.map(to_units),
+    };
+    journal::record(JournalEntry {
+        status: if fill.success { Status::Confirmed } else { Status::Reverted },
+        ..JournalEntry::new(side, token_address_str, tx_hash, bnb, Some(to_units(fill.tokens))).with_wallet(wallet)
+    });
+
+    Ok(Some(fill))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy::sol_types::SolEvent;
+
+    fn word(address: Address) -> String {
+        format!("0x{:0>64}", hex::encode(address))
+    }
+
+    fn log(address: Address, topics: Vec<String>, data: String) -> serde_json::Value {
+        json!({
+            "address": address,
+            "topics": topics,
+            "data": data,
+            "blockHash": B256::repeat_byte(0x02),
+            "blockNumber": "0x10",
+            "transactionHash": B256::repeat_byte(0x01),
+            "transactionIndex": "0x0",
+            "logIndex": "0x0",
+            "removed": false
+        })
+    }
+
+    fn receipt(from: Address, to: Address, logs: Vec<serde_json::Value>) -> TransactionReceipt {
+        serde_json::from_value(json!({
+            "type": "0x0",
+            "status": "0x1",
+            "cumulativeGasUsed": "0x30d40",
+            "logsBloom": format!("0x{}", "00".repeat(256)),
+            "logs": logs,
+            "transactionHash": B256::repeat_byte(0x01),
+            "transactionIndex": "0x0",
+            "blockHash": B256::repeat_byte(0x02),
+            "blockNumber": "0x10",
+            "gasUsed": "0x186a0",
+            "effectiveGasPrice": "0x3b9aca00",
+            "from": from,
+            "to": to,
+            "contractAddress": null
+        })).unwrap()
+    }
+
+    #[test]
+    fn test_parse_fill_from_receipt() {
+        let token = Address::repeat_byte(0x44);
+        let wallet = Address::repeat_byte(0xaa);
+        let manager = Address::repeat_byte(0x55);
+        let amount = U256::from(1_500_000_000_000_000_000_000u128);
+        let transfer = |from: Address, to: Address, value: U256| {
+            log(token, vec![format!("{:?}", IERC20::Transfer::SIGNATURE_HASH), word(from), word(to)], format!("0x{:064x}", value))
+        };
+
+        let buy = receipt(wallet, manager, vec![
+            transfer(manager, wallet, amount),
+            transfer(manager, Address::repeat_byte(0x01), U256::from(7)),
+        ]);
+        let fill = parse_fill(&buy, Side::Buy, token, wallet);
+        assert!(fill.success);
+        assert_eq!(fill.tokens, amount);
+        assert_eq!(fill.bnb_received, None);
+        assert_eq!(fill.gas_paid, U256::from(100_000u64 * 1_000_000_000u64));
+        assert_eq!(fill.block_number, Some(16));
+        assert_eq!(to_units(fill.tokens), 1500.0);
+
+        // A PancakeSwap sell is paid out by the router unwrapping WBNB
+        let router = Address::repeat_byte(0x10);
+        let proceeds = to_wei(0.25).unwrap();
+        let sell = receipt(wallet, router, vec![
+            transfer(wallet, Address::repeat_byte(0x77), amount),
+            log(WBNB, vec![format!("{:?}", IWBNB::Withdrawal::SIGNATURE_HASH), word(router)], format!("0x{:064x}", proceeds)),
+        ]);
+        let fill = parse_fill(&sell, Side::Sell, token, wallet);
+        assert_eq!(fill.tokens, amount);
+        assert_eq!(fill.bnb_received, Some(proceeds));
+
+        assert_eq!(to_wei(20.5).unwrap(), U256::from(20_500_000_000_000_000_000u128));
+    }
 }
@@ -0,0 +1,141 @@
+use regex::Regex;
+use std::sync::OnceLock;
+
+use crate::decoder::{TokenCreate, contains_chinese};
+
+// Words that start sentences or name the usual suspects rather than a meme
+const STOP_WORDS: [&str; 14] = ["i", "a", "the", "my", "we", "our", "this", "that", "it", "rt", "binance", "bnb", "cz", "new"];
+
+fn quoted_pattern() -> &'static Regex {
+    static PATTERN: OnceLock<Regex> = OnceLock::new();
+    PATTERN.get_or_init(|| Regex::new(r#""([^"\n]{2,40})"|“([^”\n]{2,40})”|「([^」\n]{1,40})」|『([^』\n]{1,40})』|《([^》\n]{1,40})》"#).unwrap())
+}
+
+fn tag_pattern() -> &'static Regex {
+    static PATTERN: OnceLock<Regex> = OnceLock::new();
+    PATTERN.get_or_init(|| Regex::new(r"[#$]([\p{L}\p{N}_]{2,30})").unwrap())
+}
+
+fn cjk_pattern() -> &'static Regex {
+    static PATTERN: OnceLock<Regex> = OnceLock::new();
+    PATTERN.get_or_init(|| Regex::new(r"[\p{Han}]{2,12}").unwrap())
+}
+
+fn capitalised_pattern() -> &'static Regex {
+    static PATTERN: OnceLock<Regex> = OnceLock::new();
+    // Two or more capitalised words, allowing short connectors ("Freedom of Money")
+    PATTERN.get_or_init(|| Regex::new(r"\b[A-Z][\w']*(?:\s+(?:of|the|and|de|to|[A-Z][\w']*))*\s+[A-Z][\w']*\b").unwrap())
+}
+
+// Candidate token names from a tweet, most specific first: quotes, tags, CJK runs, capitalised phrases
+pub fn extract(text: &str) -> Vec<String> {
+    let text: String = text.split_whitespace()
+        .filter(|w| !w.contains("://"))
+        .collect::<Vec<_>>()
+        .join(" ");
+    let mut targets: Vec<String> = Vec::new();
+    let mut push = |candidate: &str| {
+        let candidate = candidate.trim().trim_matches(|c: char| !c.is_alphanumeric());
+        let key = normalize(candidate);
+        if key.is_empty() || STOP_WORDS.contains(&key.as_str()) {
+            return;
+        }
+        if !targets.iter().any(|t| normalize(t) == key) {
+            targets.push(candidate.to_string());
+        }
+    };
+
+    let mut quoted_cjk = false;
+    for caps in quoted_pattern().captures_iter(&text) {
+        if let Some(quote) = caps.iter().skip(1).flatten().next() {
+            quoted_cjk |= contains_chinese(quote.as_str());
+            push(quote.as_str());
+        }
+    }
+    for caps in tag_pattern().captures_iter(&text) {
+        push(&caps[1]);
+    }
+    // Once a name is quoted, the Chinese around it is the sentence, not another name ("「币安人生」要来了")
+    if !quoted_cjk {
+        for run in cjk_pattern().find_iter(&text) {
+            push(run.as_str());
+        }
+    }
+    for phrase in capitalised_pattern().find_iter(&text) {
+        // Drop a leading stop word ("My Freedom Coin" -> "Freedom Coin")
+        let phrase = phrase.as_str();
+        let trimmed = match phrase.split_once(' ') {
+            Some((first, rest)) if STOP_WORDS.contains(&first.to_lowercase().as_str()) && rest.contains(' ') => rest,
+            _ => phrase,
+        };
+        // A usual suspect anywhere in the phrase makes it a product, not a meme ("Binance Academy")
+        if trimmed.split_whitespace()
+            .filter(|w| w.starts_with(|c: char| c.is_uppercase()))
+            .any(|w| STOP_WORDS.contains(&normalize(w).as_str())) {
+            continue;
+        }
+        push(trimmed);
+    }
+    targets
+}
+
+// Lowercased letters and digits only, so "Freedom of Money", "FreedomOfMoney" and "$FREEDOMOFMONEY" agree
+pub fn normalize(s: &str) -> String {
+    s.chars().filter(|c| c.is_alphanumeric()).flat_map(char::to_lowercase).collect()
+}
+
+// The target a freshly created token matches, if any: exact on name or symbol, or a name
+// that contains the whole target with little added ("Freedom of Money Official"). A name that
+// is only a fragment of the target is a copycat, not a match.
+pub fn matching_target<'a>(targets: &'a [String], token: &TokenCreate) -> Option<&'a str> {
+    let name = normalize(&token.name);
+    let symbol = normalize(&token.symbol);
+    targets.iter().map(String::as_str).find(|target| {
+        let target = normalize(target);
+        [&name, &symbol].into_iter().filter(|s| !s.is_empty()).any(|s| {
+            if *s == target {
+                return true;
+            }
+            let (target_len, len) = (target.chars().count(), s.chars().count());
+            let long_enough = if contains_chinese(&target) { target_len >= 2 } else { target_len >= 4 };
+            long_enough && target_len * 2 >= len && s.contains(target.as_str())
+        })
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy::primitives::Address;
+
+    fn token(name: &str, symbol: &str) -> TokenCreate {
+        TokenCreate { creator: Address::ZERO, token: Address::ZERO, name: name.to_string(), symbol: symbol.to_string() }
+    }
+
+    #[test]
+    fn test_extract_targets() {
+        let targets = extract("My book \"Freedom of Money\" is out! #BNBSummer $BROCCOLI 币安人生 https://t.co/Xyz thanks to Binance Academy");
+        assert_eq!(targets, vec!["Freedom of Money", "BNBSummer", "BROCCOLI", "币安人生"]);
+
+        // The bracketed name alone, not the rest of the sentence
+        assert_eq!(extract("「币安人生」要来了"), vec!["币安人生"]);
+        // Phrases naming Binance, BNB or CZ are not meme names
+        assert!(extract("Join the BNB Chain Summit with CZ Binance").is_empty());
+        assert_eq!(extract("My Freedom Coin by Binance Labs"), vec!["Freedom Coin"]);
+        assert!(extract("gm. Funds are safu.").is_empty());
+    }
+
+    #[test]
+    fn test_matching_target() {
+        let targets = vec!["Freedom of Money".to_string(), "币安人生".to_string()];
+        assert_eq!(matching_target(&targets, &token("FreedomOfMoney", "FOM")), Some("Freedom of Money"));
+        assert_eq!(matching_target(&targets, &token("Freedom of Money Official", "FREE")), Some("Freedom of Money"));
+        assert_eq!(matching_target(&targets, &token("币安人生", "BALS")), Some("币安人生"));
+        assert_eq!(matching_target(&targets, &token("币安人生2", "BALS")), Some("币安人生"));
+        // Fragments of a target are copycats
+        assert_eq!(matching_target(&targets, &token("人生", "人生")), None);
+        assert_eq!(matching_target(&targets, &token("Freedom Money", "FREEDOM")), None);
+        assert_eq!(matching_target(&targets, &token("Money", "MONEY")), None);
+        assert_eq!(matching_target(&targets, &token("狗狗", "DOGE")), None);
+    }
+}
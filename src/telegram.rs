@@ -0,0 +1,236 @@
+use async_trait::async_trait;
+use serde::Deserialize;
+use std::env;
+use std::time::{Duration, Instant};
+use tokio::sync::mpsc;
+use anyhow::{Result, anyhow};
+
+use crate::rules::{self, FeedType, MessageText, RuleSet, TriggerConfig};
+use crate::triggers::{TriggerEvent, TriggerSource};
+
+// Seconds Telegram holds each getUpdates request open
+const LONG_POLL_SECS: u64 = 30;
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct TelegramConfig {
+    // Defaults to the public Bot API; the bot token comes from TELEGRAM_BOT_TOKEN
+    #[serde(default = "default_api_url")]
+    pub api_url: String,
+    pub chats: Vec<TelegramChat>,
+}
+
+fn default_api_url() -> String {
+    "https://api.telegram.org".to_string()
+}
+
+// A channel or group the bot has been added to
+#[derive(Debug, Clone, Deserialize)]
+pub struct TelegramChat {
+    pub id: i64,
+    // Alpha channels post in their own voice, so any mention of the account counts by default
+    #[serde(default = "default_feed")]
+    pub feed: FeedType,
+    #[serde(default = "rules::default_rule_set")]
+    pub rules: String,
+    #[serde(default)]
+    pub label: Option<String>,
+}
+
+fn default_feed() -> FeedType {
+    FeedType::NewsBot
+}
+
+impl TelegramChat {
+    pub fn name(&self) -> String {
+        self.label.clone().unwrap_or_else(|| self.id.to_string())
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct ApiResponse<T> {
+    ok: bool,
+    #[serde(default)]
+    description: Option<String>,
+    result: Option<T>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Update {
+    update_id: i64,
+    #[serde(default)]
+    message: Option<TgMessage>,
+    #[serde(default)]
+    channel_post: Option<TgMessage>,
+}
+
+#[derive(Debug, Deserialize)]
+struct TgMessage {
+    message_id: i64,
+    date: i64,
+    chat: Chat,
+    #[serde(default)]
+    text: Option<String>,
+    #[serde(default)]
+    caption: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Chat {
+    id: i64,
+    #[serde(default)]
+    title: Option<String>,
+    #[serde(default)]
+    username: Option<String>,
+}
+
+pub struct TelegramSource {
+    api_url: String,
+    token: String,
+    chats: Vec<(TelegramChat, RuleSet)>,
+    offset: Option<i64>,
+}
+
+// A single Bot API poller covers every configured chat
+pub fn sources(config: &TriggerConfig) -> Result<Vec<Box<dyn TriggerSource>>> {
+    let Some(telegram) = &config.telegram else {
+        return Ok(Vec::new());
+    };
+    let token = env::var("TELEGRAM_BOT_TOKEN")
+        .map_err(|_| anyhow!("Telegram chats are configured but TELEGRAM_BOT_TOKEN is not set"))?;
+    Ok(vec![Box::new(TelegramSource::new(telegram, token, config))])
+}
+
+impl TelegramSource {
+    pub fn new(telegram: &TelegramConfig, token: String, config: &TriggerConfig) -> Self {
+        Self {
+            api_url: telegram.api_url.trim_end_matches('/').to_string(),
+            token,
+            chats: telegram.chats.iter().map(|c| (c.clone(), config.rule_set(&c.rules))).collect(),
+            offset: None,
+        }
+    }
+
+    async fn poll_once(&mut self, client: &reqwest::Client, events: &mpsc::Sender<TriggerEvent>) -> Result<()> {
+        let url = format!("{}/bot{}/getUpdates", self.api_url, self.token);
+        let mut query = vec![
+            ("timeout", LONG_POLL_SECS.to_string()),
+            ("allowed_updates", r#"["message","channel_post"]"#.to_string()),
+        ];
+        if let Some(offset) = self.offset {
+            query.push(("offset", offset.to_string()));
+        }
+
+        let response: ApiResponse<Vec<Update>> = client.get(&url).query(&query).send().await?.json().await?;
+        if !response.ok {
+            return Err(anyhow!("getUpdates failed: {}", response.description.unwrap_or_default()));
+        }
+
+        let received_at = Instant::now();
+        for update in response.result.unwrap_or_default() {
+            // Acknowledge every update, matched or not, so it is not redelivered
+            self.offset = Some(update.update_id + 1);
+            let Some(message) = update.channel_post.or(update.message) else {
+                continue;
+            };
+            if let Some(event) = self.parse_message(message, received_at) {
+                events.send(event).await.map_err(|_| anyhow!("Trigger pipeline closed"))?;
+            }
+        }
+        Ok(())
+    }
+
+    fn parse_message(&self, message: TgMessage, received_at: Instant) -> Option<TriggerEvent> {
+        let (chat, rules) = self.chats.iter().find(|(c, _)| c.id == message.chat.id)?;
+        let text = message.text.or(message.caption).unwrap_or_default();
+        let now = chrono::Local::now().format("%Y-%m-%d %H:%M:%S").to_string();
+
+        let view = MessageText { content: text.clone(), embeds: Vec::new() };
+        let Some(trigger) = rules::match_message(chat.feed, rules, &view) else {
+            let title = message.chat.title.unwrap_or_else(|| chat.name());
+            println!("[{}] [Telegram] Ignored post in {}", now, title);
+            return None;
+        };
+        Some(TriggerEvent {
+            source: format!("telegram:{}", chat.name()),
+            account: trigger.account,
+            keyword: trigger.keyword,
+            text,
+            url: message.chat.username.map(|u| format!("https://t.me/{}/{}", u, message.message_id)),
+            tweet_id: None,
+            received_at,
+            // Telegram dates only have second precision
+            relay_delay_ms: Some(chrono::Utc::now().timestamp_millis() - message.date * 1000),
+        })
+    }
+}
+
+#[async_trait]
+impl TriggerSource for TelegramSource {
+    fn name(&self) -> String {
+        format!("telegram ({} chats)", self.chats.len())
+    }
+
+    async fn run(mut self: Box<Self>, events: mpsc::Sender<TriggerEvent>) -> Result<()> {
+        let client = reqwest::Client::builder().timeout(Duration::from_secs(LONG_POLL_SECS + 15)).build()?;
+        let mut backoff = Duration::from_secs(1);
+        loop {
+            match self.poll_once(&client, &events).await {
+                Ok(()) => backoff = Duration::from_secs(1),
+                Err(e) => {
+                    if events.is_closed() {
+                        return Err(e);
+                    }
+                    let now = chrono::Local::now().format("%Y-%m-%d %H:%M:%S").to_string();
+                    // Strip the bot token from request errors before logging
+                    let error = format!("{:?}", e).replace(&self.token, "<token>");
+                    eprintln!("[{}] Telegram poll error: {}. Retrying in {:?}...", now, error, backoff);
+                    tokio::time::sleep(backoff).await;
+                    backoff = (backoff * 2).min(Duration::from_secs(30));
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_bot_api_long_poll() {
+        let (requests_tx, mut requests) = mpsc::unbounded_channel();
+        let mut first = true;
+        let url = crate::testutil::mock_http(move |request| {
+            let _ = requests_tx.send(request.request_line.clone());
+            let body = if first {
+                r#"{"ok":true,"result":[
+                    {"update_id":41,"channel_post":{"message_id":5,"date":1700000000,"chat":{"id":-1009,"title":"Other"},"text":"cz book release"}},
+                    {"update_id":42,"channel_post":{"message_id":6,"date":1700000000,"chat":{"id":-1001,"title":"Alpha","username":"alpha_cn"},"text":"CZ: 新书 book is out, publish date set"}}
+                ]}"#
+            } else {
+                r#"{"ok":true,"result":[]}"#
+            };
+            first = false;
+            body.to_string()
+        }).await;
+
+        let config: TriggerConfig = serde_json::from_str(&format!(r#"{{
+            "channels": [{{"id": 1, "feed": "tweet_relay"}}],
+            "rule_sets": {{"cz": {{"accounts": ["cz"], "keywords": ["book"]}}}},
+            "telegram": {{"api_url": "{}", "chats": [{{"id": -1001, "rules": "cz", "label": "alpha"}}]}}
+        }}"#, url)).unwrap();
+        let source = TelegramSource::new(config.telegram.as_ref().unwrap(), "123:abc".to_string(), &config);
+        let (tx, mut rx) = mpsc::channel(8);
+        tokio::spawn(Box::new(source).run(tx));
+
+        let event = tokio::time::timeout(Duration::from_secs(5), rx.recv()).await.unwrap().unwrap();
+        assert_eq!(event.source, "telegram:alpha");
+        assert_eq!((event.account.as_str(), event.keyword.as_str()), ("cz", "book"));
+        assert_eq!(event.url.as_deref(), Some("https://t.me/alpha_cn/6"));
+        assert!(tokio::time::timeout(Duration::from_millis(200), rx.recv()).await.is_err());
+
+        // The second poll acknowledges both updates, including the unwatched chat
+        assert!(requests.recv().await.unwrap().starts_with("GET /bot123:abc/getUpdates?"));
+        assert!(requests.recv().await.unwrap().contains("offset=43"));
+    }
+}
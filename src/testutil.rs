@@ -0,0 +1,48 @@
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpListener;
+
+pub struct Request {
+    // e.g. "GET /tweets?since_id=101 HTTP/1.1"
+    pub request_line: String,
+    pub body: String,
+}
+
+// A local HTTP/1.1 server answering every request with the handler's JSON body; returns its base URL
+pub async fn mock_http<F>(mut handler: F) -> String
+where
+    F: FnMut(&Request) -> String + Send + 'static,
+{
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move {
+        while let Ok((mut conn, _)) = listener.accept().await {
+            let mut buf = Vec::new();
+            let mut chunk = [0u8; 4096];
+            // Read the headers, then as much body as they announce
+            let request = loop {
+                let n = conn.read(&mut chunk).await.unwrap_or(0);
+                if n == 0 {
+                    break None;
+                }
+                buf.extend_from_slice(&chunk[..n]);
+                let raw = String::from_utf8_lossy(&buf).to_string();
+                if let Some((head, body)) = raw.split_once("\r\n\r\n") {
+                    let length = head.lines()
+                        .find_map(|l| l.to_lowercase().strip_prefix("content-length:").map(|v| v.trim().parse::<usize>().unwrap()))
+                        .unwrap_or(0);
+                    if body.len() >= length {
+                        let request_line = head.lines().next().unwrap_or_default().to_string();
+                        break Some(Request { request_line, body: body.to_string() });
+                    }
+                }
+            };
+            let Some(request) = request else {
+                continue;
+            };
+            let body = handler(&request);
+            let response = format!("HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}", body.len(), body);
+            let _ = conn.write_all(response.as_bytes()).await;
+        }
+    });
+    format!("http://{}", addr)
+}
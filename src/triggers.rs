@@ -0,0 +1,222 @@
+use async_trait::async_trait;
+use serenity::http::Http;
+use serenity::model::id::ChannelId;
+use std::collections::hash_map::DefaultHasher;
+use std::env;
+use std::hash::{Hash, Hasher};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::{mpsc, Mutex};
+use anyhow::Result;
+
+use crate::{SharedState, monitor, targets, tweet, BUY_WINDOW, metrics::{self, METRICS}};
+
+// A matched tweet from any source, ready to open the buy window
+#[derive(Debug, Clone)]
+pub struct TriggerEvent {
+    // Where the trigger came from, e.g. "discord:relay-a" or "feed:x-stream"
+    pub source: String,
+    pub account: String,
+    pub keyword: String,
+    pub text: String,
+    pub url: Option<String>,
+    // Set when the source knows the tweet id; otherwise it is looked for in the URL and text
+    pub tweet_id: Option<String>,
+    pub received_at: Instant,
+    // Tweet/post time -> receipt, when the source reports it
+    pub relay_delay_ms: Option<i64>,
+}
+
+// Anything that can watch for tweets and push matches into the trigger pipeline
+#[async_trait]
+pub trait TriggerSource: Send {
+    fn name(&self) -> String;
+    async fn run(self: Box<Self>, events: mpsc::Sender<TriggerEvent>) -> Result<()>;
+}
+
+pub fn spawn_source(source: Box<dyn TriggerSource>, events: mpsc::Sender<TriggerEvent>) {
+    tokio::spawn(async move {
+        let name = source.name();
+        if let Err(e) = source.run(events).await {
+            let now = chrono::Local::now().format("%Y-%m-%d %H:%M:%S").to_string();
+            eprintln!("[{}] Trigger source {} stopped: {:?}", now, name, e);
+        }
+    });
+}
+
+// Single consumer for every source, so the window and monitors are handled in one place
+pub async fn dispatch(state: Arc<Mutex<SharedState>>, http: Arc<Http>, mut events: mpsc::Receiver<TriggerEvent>) {
+    let mut dedup = Dedup::from_env();
+    while let Some(event) = events.recv().await {
+        if let Some(first) = dedup.check(&event) {
+            let now = chrono::Local::now().format("%Y-%m-%d %H:%M:%S").to_string();
+            println!("[{}] [Dedup] Trigger via {} merged into window opened by {} {}ms earlier",
+                now, event.source, first.source, event.received_at.saturating_duration_since(first.at).as_millis());
+            metrics::inc(&METRICS.triggers_deduplicated);
+            continue;
+        }
+        fire(&state, &http, event).await;
+    }
+}
+
+// Lowercased words with URLs and punctuation dropped, so relays that reformat a tweet still agree
+pub fn normalize_text(text: &str) -> String {
+    text.split_whitespace()
+        .filter(|word| !word.starts_with("http://") && !word.starts_with("https://"))
+        .map(|word| word.chars().filter(|c| c.is_alphanumeric()).collect::<String>().to_lowercase())
+        .filter(|word| !word.is_empty())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+// Keys identifying the tweet behind a trigger; any shared key marks a duplicate
+pub fn fingerprints(event: &TriggerEvent) -> Vec<String> {
+    let mut keys = Vec::new();
+    let tweet_id = event.tweet_id.clone().or_else(|| {
+        [event.url.as_deref(), Some(event.text.as_str())].into_iter().flatten()
+            .find_map(tweet::tweet_id)
+    });
+    if let Some(id) = tweet_id {
+        keys.push(format!("id:{}", id));
+    }
+    let text = normalize_text(&event.text);
+    if !text.is_empty() {
+        let mut hasher = DefaultHasher::new();
+        text.hash(&mut hasher);
+        keys.push(format!("text:{:016x}", hasher.finish()));
+    }
+    keys
+}
+
+#[derive(Debug, Clone)]
+pub struct FirstSeen {
+    pub source: String,
+    pub at: Instant,
+    keys: Vec<String>,
+}
+
+// Remembers recent triggers so repeat deliveries (other relays, edits) don't reopen the window
+pub struct Dedup {
+    horizon: Duration,
+    recent: Vec<FirstSeen>,
+}
+
+impl Dedup {
+    pub fn new(horizon: Duration) -> Self {
+        Self { horizon, recent: Vec::new() }
+    }
+
+    // TRIGGER_DEDUP_SECS, default 120s
+    pub fn from_env() -> Self {
+        let secs = env::var("TRIGGER_DEDUP_SECS").ok().and_then(|v| v.parse().ok()).unwrap_or(120);
+        Self::new(Duration::from_secs(secs))
+    }
+
+    // Returns the first sighting if this event is a duplicate, otherwise records it
+    pub fn check(&mut self, event: &TriggerEvent) -> Option<FirstSeen> {
+        let horizon = self.horizon;
+        self.recent.retain(|seen| event.received_at.saturating_duration_since(seen.at) < horizon);
+
+        let keys = fingerprints(event);
+        if let Some(seen) = self.recent.iter_mut().find(|seen| keys.iter().any(|k| seen.keys.contains(k))) {
+            // Learn the new keys too, e.g. a relay that adds the URL the first one lacked
+            for key in keys {
+                if !seen.keys.contains(&key) {
+                    seen.keys.push(key);
+                }
+            }
+            return Some(seen.clone());
+        }
+        if !keys.is_empty() {
+            self.recent.push(FirstSeen { source: event.source.clone(), at: event.received_at, keys });
+        }
+        None
+    }
+}
+
+async fn fire(state: &Arc<Mutex<SharedState>>, http: &Arc<Http>, event: TriggerEvent) {
+    let now = chrono::Local::now().format("%Y-%m-%d %H:%M:%S").to_string();
+    println!("[{}] 🔥 Trigger from @{} ('{}') via {}! Opening {}-second buy window.",
+        now, event.account, event.keyword, event.source, BUY_WINDOW.as_secs());
+    metrics::inc(&METRICS.triggers_seen);
+    if let Some(delay) = event.relay_delay_ms {
+        println!("[{}] [Latency] Relay delay (post -> receipt): {}ms", now, delay);
+    }
+
+    let targets = targets::extract(&event.text);
+    if targets.is_empty() {
+        println!("[{}] [Targets] None found in tweet; buying any Chinese-named token", now);
+    } else {
+        println!("[{}] [Targets] {:?}", now, targets);
+    }
+
+    let notify_channel = {
+        let mut state = state.lock().await;
+        state.last_cz_tweet_time = Some(event.received_at);
+        state.last_trigger_relay_ms = event.relay_delay_ms;
+        state.targets = targets.clone();
+        state.triggers.notify_channel()
+    };
+
+    let mut alert = format!("🔥 Trigger from @{} ('{}') via {}! Sniper window OPEN for {}s.",
+        event.account, event.keyword, event.source, BUY_WINDOW.as_secs());
+    if !targets.is_empty() {
+        alert.push_str(&format!("\n🎯 Targets: {}", targets.join(", ")));
+    }
+    if let Some(url) = &event.url {
+        alert.push_str(&format!("\n{}", url));
+    }
+    let _ = ChannelId::new(notify_channel).say(http, alert).await;
+
+    // Monitor for 10 seconds after detection
+    monitor::spawn_sniper(Arc::clone(state), Arc::clone(http), notify_channel, Duration::from_secs(10));
+
+    // Log when the window closes
+    tokio::spawn(async move {
+        tokio::time::sleep(BUY_WINDOW).await;
+        let now_close = chrono::Local::now().format("%Y-%m-%d %H:%M:%S").to_string();
+        println!("[{}] ⏱️ {}-second buy window has closed.", now_close, BUY_WINDOW.as_secs());
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn event(source: &str, text: &str, url: Option<&str>, at: Instant) -> TriggerEvent {
+        TriggerEvent {
+            source: source.to_string(),
+            account: "cz_binance".to_string(),
+            keyword: "book".to_string(),
+            text: text.to_string(),
+            url: url.map(str::to_string),
+            tweet_id: None,
+            received_at: at,
+            relay_delay_ms: None,
+        }
+    }
+
+    #[test]
+    fn test_dedup_across_sources() {
+        let start = Instant::now();
+        let mut dedup = Dedup::new(Duration::from_secs(60));
+
+        let relay = event("discord:relay-a", "My book is out! https://t.co/abc", Some("https://twitter.com/cz_binance/status/1790000000000000001"), start);
+        assert!(dedup.check(&relay).is_none());
+
+        // Same tweet id through a different domain and text formatting
+        let feed = event("feed:x", "my BOOK is out", Some("https://x.com/cz_binance/status/1790000000000000001"), start + Duration::from_millis(300));
+        assert_eq!(dedup.check(&feed).unwrap().source, "discord:relay-a");
+
+        // No URL at all, but the normalised text matches
+        let bridge = event("telegram:alpha", "My book is out!!", None, start + Duration::from_secs(2));
+        assert!(dedup.check(&bridge).is_some());
+
+        let other = event("discord:relay-b", "Publishing another book", None, start + Duration::from_secs(3));
+        assert!(dedup.check(&other).is_none());
+
+        // Outside the horizon the same tweet opens a new window
+        let late = event("discord:relay-b", "My book is out!", None, start + Duration::from_secs(61));
+        assert!(dedup.check(&late).is_none());
+    }
+}
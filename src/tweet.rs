@@ -0,0 +1,215 @@
+use regex::Regex;
+use serde::Deserialize;
+use std::sync::OnceLock;
+
+use crate::rules::{EmbedText, MessageText};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TweetKind {
+    Original,
+    Reply,
+    Retweet,
+    Quote,
+}
+
+// What a relay message tells us about the tweet behind it
+#[derive(Debug, Clone, PartialEq)]
+pub struct Tweet {
+    pub id: Option<String>,
+    pub author: Option<String>,
+    pub text: String,
+    // Text of the quoted tweet, or of the original for retweets that carry it separately
+    pub quoted_text: Option<String>,
+    // Handle of the account being retweeted, quoted or replied to
+    pub referenced_author: Option<String>,
+    pub media: Vec<String>,
+    pub timestamp: Option<chrono::DateTime<chrono::Utc>>,
+    pub url: Option<String>,
+    pub kind: TweetKind,
+}
+
+fn status_pattern() -> &'static Regex {
+    static PATTERN: OnceLock<Regex> = OnceLock::new();
+    PATTERN.get_or_init(|| Regex::new(r"(?i)(?:twitter|x)\.com/([A-Za-z0-9_]+|i/web)/status(?:es)?/(\d+)").unwrap())
+}
+
+fn handle_pattern() -> &'static Regex {
+    static PATTERN: OnceLock<Regex> = OnceLock::new();
+    PATTERN.get_or_init(|| Regex::new(r"@([A-Za-z0-9_]{1,15})").unwrap())
+}
+
+fn profile_pattern() -> &'static Regex {
+    static PATTERN: OnceLock<Regex> = OnceLock::new();
+    PATTERN.get_or_init(|| Regex::new(r"(?i)(?:twitter|x)\.com/([A-Za-z0-9_]{1,15})/?$").unwrap())
+}
+
+// First tweet URL in the text as (handle, id)
+pub fn find_status(text: &str) -> Option<(String, String)> {
+    status_pattern().captures(text).map(|c| (c[1].to_string(), c[2].to_string()))
+}
+
+pub fn tweet_id(text: &str) -> Option<String> {
+    find_status(text).map(|(_, id)| id)
+}
+
+fn first_handle(text: &str) -> Option<String> {
+    handle_pattern().captures(text).map(|c| c[1].to_string())
+}
+
+impl Tweet {
+    // Reads the relay's header line, the first embed (the tweet) and any follow-up embeds or fields (quotes)
+    pub fn from_message(msg: &MessageText) -> Self {
+        let main = msg.embeds.first().cloned().unwrap_or_default();
+        let header = [Some(msg.content.as_str()), main.title.as_deref()]
+            .into_iter().flatten().collect::<Vec<_>>().join("\n");
+        let header_lower = header.to_lowercase();
+        let footer_lower = main.footer.as_deref().unwrap_or_default().to_lowercase();
+
+        let mut text = main.description.clone().unwrap_or_else(|| msg.content.clone());
+
+        let status = [main.url.as_deref(), Some(msg.content.as_str()), main.description.as_deref()]
+            .into_iter().flatten().find_map(find_status);
+
+        let author = header_handle(&header)
+            .or_else(|| main.author.as_deref().and_then(first_handle))
+            .or_else(|| main.author_url.as_deref().and_then(|u| profile_pattern().captures(u).map(|c| c[1].to_string())))
+            .or_else(|| status.as_ref().map(|(handle, _)| handle.clone()).filter(|h| h != "i/web"));
+
+        let mut quoted_text = quote_field(&main)
+            .or_else(|| msg.embeds.get(1).and_then(|e| e.description.clone()));
+        let mut referenced_author = msg.embeds.get(1)
+            .and_then(|e| e.author.as_deref().and_then(first_handle));
+
+        let kind = if header_lower.contains("retweet") || footer_lower.contains("retweet") || text.starts_with("RT @") {
+            // "RT @user: body" carries the original inline
+            if let Some(rest) = text.strip_prefix("RT @") {
+                if let Some((handle, body)) = rest.split_once(':') {
+                    referenced_author = Some(handle.to_string());
+                    quoted_text.get_or_insert_with(|| body.trim().to_string());
+                    text = body.trim().to_string();
+                }
+            }
+            if referenced_author.is_none() {
+                referenced_author = handle_pattern().captures_iter(&header).nth(1).map(|c| c[1].to_string());
+            }
+            TweetKind::Retweet
+        } else if header_lower.contains("repl") || footer_lower.contains("repl") || text.starts_with('@') {
+            if referenced_author.is_none() {
+                referenced_author = header_lower.find("replying to")
+                    .and_then(|i| first_handle(&header[i..]))
+                    .or_else(|| text.starts_with('@').then(|| first_handle(&text)).flatten());
+            }
+            TweetKind::Reply
+        } else if quoted_text.is_some() || header_lower.contains("quote") {
+            TweetKind::Quote
+        } else {
+            TweetKind::Original
+        };
+
+        let mut media: Vec<String> = msg.embeds.iter().filter_map(|e| e.image.clone()).collect();
+        for word in text.split_whitespace() {
+            if word.contains("pbs.twimg.com/media") || word.contains("video.twimg.com") {
+                media.push(word.trim_end_matches(|c: char| !c.is_alphanumeric()).to_string());
+            }
+        }
+        media.dedup();
+
+        let timestamp = main.timestamp.as_deref()
+            .and_then(|t| chrono::DateTime::parse_from_rfc3339(t).ok())
+            .map(|t| t.with_timezone(&chrono::Utc));
+
+        Tweet {
+            id: status.as_ref().map(|(_, id)| id.clone()),
+            author,
+            text,
+            quoted_text,
+            referenced_author,
+            media,
+            timestamp,
+            url: main.url.clone().or_else(|| status.map(|(handle, id)| format!("https://x.com/{}/status/{}", handle, id))),
+            kind,
+        }
+    }
+}
+
+// "New Tweet from @cz_binance", "@cz_binance retweeted @x", ...
+fn header_handle(header: &str) -> Option<String> {
+    let lower = header.to_lowercase();
+    for marker in ["tweet from", "retweet from", "reply from", "quote from", "posted by"] {
+        if let Some(i) = lower.find(marker) {
+            if let Some(handle) = first_handle(&header[i..]) {
+                return Some(handle);
+            }
+        }
+    }
+    // Otherwise the first handle, as long as it leads the line ("@cz_binance retweeted ...")
+    header.trim_start_matches(['*', '_', ' ']).starts_with('@').then(|| first_handle(header)).flatten()
+}
+
+fn quote_field(embed: &EmbedText) -> Option<String> {
+    embed.fields.iter()
+        .find(|(name, _)| {
+            let name = name.to_lowercase();
+            name.contains("quote") || name.contains("retweet")
+        })
+        .map(|(_, value)| value.clone())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn relay(content: &str, embed: EmbedText) -> MessageText {
+        MessageText { content: content.to_string(), embeds: vec![embed] }
+    }
+
+    #[test]
+    fn test_original_tweet() {
+        let msg = relay("New Tweet from @cz_binance", EmbedText {
+            author: Some("CZ 🔶 BNB (@cz_binance)".to_string()),
+            author_url: Some("https://twitter.com/cz_binance".to_string()),
+            description: Some("My book \"Freedom of Money\" is out. https://pbs.twimg.com/media/abc.jpg".to_string()),
+            url: Some("https://twitter.com/cz_binance/status/1912345678901234567".to_string()),
+            image: Some("https://pbs.twimg.com/media/cover.jpg".to_string()),
+            timestamp: Some("2025-04-16T09:30:00.000Z".to_string()),
+            ..Default::default()
+        });
+        let tweet = Tweet::from_message(&msg);
+        assert_eq!(tweet.kind, TweetKind::Original);
+        assert_eq!(tweet.id.as_deref(), Some("1912345678901234567"));
+        assert_eq!(tweet.author.as_deref(), Some("cz_binance"));
+        assert_eq!(tweet.media, vec!["https://pbs.twimg.com/media/cover.jpg", "https://pbs.twimg.com/media/abc.jpg"]);
+        assert_eq!(tweet.timestamp.unwrap().timestamp(), 1744795800);
+    }
+
+    #[test]
+    fn test_replies_retweets_and_quotes() {
+        let reply = relay("New Tweet from @cz_binance", EmbedText {
+            description: Some("@heyibinance congrats on the release".to_string()),
+            ..Default::default()
+        });
+        let tweet = Tweet::from_message(&reply);
+        assert_eq!(tweet.kind, TweetKind::Reply);
+        assert_eq!(tweet.referenced_author.as_deref(), Some("heyibinance"));
+
+        let retweet = relay("New Tweet from @cz_binance", EmbedText {
+            description: Some("RT @BNBCHAIN: BNB Chain release notes are live".to_string()),
+            ..Default::default()
+        });
+        let tweet = Tweet::from_message(&retweet);
+        assert_eq!(tweet.kind, TweetKind::Retweet);
+        assert_eq!(tweet.author.as_deref(), Some("cz_binance"));
+        assert_eq!(tweet.referenced_author.as_deref(), Some("BNBCHAIN"));
+        assert_eq!(tweet.text, "BNB Chain release notes are live");
+
+        let quote = relay("New Tweet from @cz_binance", EmbedText {
+            description: Some("This book 👇".to_string()),
+            fields: vec![("Quoted Tweet".to_string(), "Pre-orders open today".to_string())],
+            ..Default::default()
+        });
+        let tweet = Tweet::from_message(&quote);
+        assert_eq!(tweet.kind, TweetKind::Quote);
+        assert_eq!(tweet.quoted_text.as_deref(), Some("Pre-orders open today"));
+    }
+}
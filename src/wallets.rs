@@ -0,0 +1,284 @@
+use alloy::{
+    primitives::Address,
+    providers::Provider,
+    signers::local::PrivateKeySigner,
+    transports::Transport,
+};
+use std::env;
+use std::io::IsTerminal;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::OnceLock;
+use tokio::sync::Mutex;
+use anyhow::{Result, anyhow};
+
+use crate::metrics::METRICS;
+use crate::signer::{self, LocalKey, TransactionSigner};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Strategy {
+    // Each buy goes out from the next wallet in turn
+    RoundRobin,
+    // Each buy is divided evenly across `n` wallets and sent from all of them at once
+    Split(usize),
+}
+
+impl Strategy {
+    pub fn from_env() -> Result<Self> {
+        match env::var("WALLET_STRATEGY").unwrap_or_default().to_lowercase().as_str() {
+            "" | "round_robin" => Ok(Strategy::RoundRobin),
+            "split" => match env::var("WALLET_SPLIT") {
+                Ok(n) => n.trim().parse().map(Strategy::Split)
+                    .map_err(|_| anyhow!("Invalid WALLET_SPLIT '{}' (expected a number of wallets)", n)),
+                Err(_) => Ok(Strategy::Split(usize::MAX)),
+            },
+            other => Err(anyhow!("Unknown WALLET_STRATEGY '{}' (expected round_robin or split)", other)),
+        }
+    }
+
+    // How many wallets each buy goes out from
+    pub fn legs(&self, wallets: usize) -> usize {
+        match *self {
+            Strategy::RoundRobin => 1,
+            Strategy::Split(n) => n.clamp(1, wallets.max(1)),
+        }
+    }
+
+    // Which wallets a buy of `amount` BNB goes out from, and how much each spends
+    pub fn plan(&self, wallets: usize, cursor: usize, amount: f64) -> Vec<(usize, f64)> {
+        if wallets == 0 {
+            return Vec::new();
+        }
+        let n = self.legs(wallets);
+        (0..n).map(|i| ((cursor + i) % wallets, amount / n as f64)).collect()
+    }
+}
+
+// Hands out nonces locally so back-to-back transactions from one wallet never collide
+#[derive(Default)]
+pub struct NonceManager {
+    next: Mutex<Option<u64>>,
+}
+
+impl NonceManager {
+    pub async fn reserve<P, T>(&self, provider: &P, address: Address) -> Result<u64>
+    where
+        P: Provider<T>,
+        T: Transport + Clone,
+    {
+        let mut next = self.next.lock().await;
+        let nonce = match *next {
+            Some(nonce) => nonce,
+            None => provider.get_transaction_count(address).pending().await
+                .inspect_err(|_| METRICS.rpc_error("eth_getTransactionCount"))?,
+        };
+        *next = Some(nonce + 1);
+        Ok(nonce)
+    }
+
+    // After a failed broadcast the chain is the only reliable source again
+    pub async fn reset(&self) {
+        *self.next.lock().await = None;
+    }
+}
+
+pub struct Wallet {
+    pub signer: Box<dyn TransactionSigner>,
+    pub nonces: NonceManager,
+}
+
+impl Wallet {
+    pub fn new(signer: Box<dyn TransactionSigner>) -> Self {
+        Wallet { signer, nonces: NonceManager::default() }
+    }
+
+    pub fn address(&self) -> Address {
+        self.signer.address()
+    }
+}
+
+pub struct WalletSet {
+    pub wallets: Vec<Wallet>,
+    pub strategy: Strategy,
+    cursor: AtomicUsize,
+}
+
+impl WalletSet {
+    pub fn new(signers: Vec<Box<dyn TransactionSigner>>, strategy: Strategy) -> Result<Self> {
+        if signers.is_empty() {
+            return Err(anyhow!("No wallets configured"));
+        }
+        Ok(WalletSet { wallets: signers.into_iter().map(Wallet::new).collect(), strategy, cursor: AtomicUsize::new(0) })
+    }
+
+    // A remote signer (SIGNER_URL), keystores from WALLET_KEYSTORES (files or directories, comma
+    // separated) or KEYSTORE_DIR, else the plaintext PRIVATE_KEY
+    pub fn from_env() -> Result<Self> {
+        let strategy = Strategy::from_env()?;
+        if let Some(signers) = signer::remote_from_env()? {
+            return WalletSet::new(signers, strategy);
+        }
+        let paths: Vec<PathBuf> = match env::var("WALLET_KEYSTORES") {
+            Ok(keystores) => keystores.split(',').map(str::trim).filter(|p| !p.is_empty()).map(PathBuf::from).collect(),
+            Err(_) => {
+                let dir = keystore_dir();
+                if keystore_files(&dir).is_ok_and(|files| !files.is_empty()) { vec![dir] } else { Vec::new() }
+            }
+        };
+        if !paths.is_empty() {
+            return WalletSet::new(load_keystores(&paths, &passphrase()?)?.into_iter().map(local).collect(), strategy);
+        }
+        let signer: PrivateKeySigner = env::var("PRIVATE_KEY")
+            .map_err(|_| anyhow!("No wallet configured: create one with `cargo run --release --bin keystore -- create`"))?
+            .parse()?;
+        eprintln!("⚠️ Using plaintext PRIVATE_KEY; move it into a keystore with `cargo run --release --bin keystore -- import --from-env`");
+        WalletSet::new(vec![local(signer)], strategy)
+    }
+
+    pub fn primary(&self) -> &Wallet {
+        &self.wallets[0]
+    }
+
+    pub fn addresses(&self) -> Vec<Address> {
+        self.wallets.iter().map(Wallet::address).collect()
+    }
+
+    // The wallets (and BNB each) for the next buy, advancing the rotation
+    pub fn plan_buy(&self, amount: f64) -> Vec<(&Wallet, f64)> {
+        // One atomic step, so concurrent buys never start from the same wallet
+        let cursor = self.cursor.fetch_add(self.strategy.legs(self.wallets.len()), Ordering::Relaxed);
+        self.strategy.plan(self.wallets.len(), cursor, amount)
+            .into_iter()
+            .map(|(i, amount)| (&self.wallets[i], amount))
+            .collect()
+    }
+}
+
+// Where the keystore CLI writes and, without WALLET_KEYSTORES, where the bot looks
+pub fn keystore_dir() -> PathBuf {
+    PathBuf::from(env::var("KEYSTORE_DIR").unwrap_or_else(|_| "keystore".to_string()))
+}
+
+pub fn keystore_files(path: &Path) -> Result<Vec<PathBuf>> {
+    if !path.is_dir() {
+        return Ok(vec![path.to_path_buf()]);
+    }
+    let mut files: Vec<_> = std::fs::read_dir(path)?
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|p| p.is_file() && !p.file_name().is_some_and(|n| n.to_string_lossy().starts_with('.')))
+        .collect();
+    files.sort();
+    Ok(files)
+}
+
+fn local(signer: PrivateKeySigner) -> Box<dyn TransactionSigner> {
+    Box::new(LocalKey(signer))
+}
+
+pub fn load_keystores(paths: &[PathBuf], passphrase: &str) -> Result<Vec<PrivateKeySigner>> {
+    let mut signers = Vec::new();
+    for path in paths {
+        for file in keystore_files(path)? {
+            let signer = PrivateKeySigner::decrypt_keystore(&file, passphrase)
+                .map_err(|e| anyhow!("Failed to unlock {}: {}", file.display(), e))?;
+            signers.push(signer);
+        }
+    }
+    Ok(signers)
+}
+
+// Name of the systemd credential (LoadCredential=/LoadCredentialEncrypted=) holding the passphrase
+const PASSPHRASE_CREDENTIAL: &str = "wallet_passphrase";
+
+fn read_secret(path: &Path) -> Result<String> {
+    let secret = std::fs::read_to_string(path).map_err(|e| anyhow!("Failed to read {}: {}", path.display(), e))?;
+    Ok(secret.trim_end_matches(['\r', '\n']).to_string())
+}
+
+// The keystore passphrase without asking: a systemd credential, WALLET_PASSPHRASE_FILE, then WALLET_PASSPHRASE
+pub fn configured_passphrase() -> Result<Option<String>> {
+    if let Ok(dir) = env::var("CREDENTIALS_DIRECTORY") {
+        let path = Path::new(&dir).join(PASSPHRASE_CREDENTIAL);
+        if path.is_file() {
+            return read_secret(&path).map(Some);
+        }
+    }
+    if let Ok(file) = env::var("WALLET_PASSPHRASE_FILE") {
+        return read_secret(Path::new(&file)).map(Some);
+    }
+    Ok(env::var("WALLET_PASSPHRASE").ok())
+}
+
+// Falls back to prompting when run from a terminal
+pub fn passphrase() -> Result<String> {
+    if let Some(passphrase) = configured_passphrase()? {
+        return Ok(passphrase);
+    }
+    if !std::io::stdin().is_terminal() {
+        return Err(anyhow!("No keystore passphrase: set WALLET_PASSPHRASE_FILE or the {} systemd credential", PASSPHRASE_CREDENTIAL));
+    }
+    Ok(rpassword::prompt_password("Keystore passphrase: ")?)
+}
+
+static WALLETS: OnceLock<WalletSet> = OnceLock::new();
+
+// Decrypts keystores (or prompts) on the calling thread, so binaries call this once at startup
+// before spawning any tasks. Failures are not cached.
+pub fn init() -> Result<&'static WalletSet> {
+    if let Some(set) = WALLETS.get() {
+        return Ok(set);
+    }
+    let set = WalletSet::from_env()?;
+    Ok(WALLETS.get_or_init(|| set))
+}
+
+// The set loaded by init; never decrypts, so it is safe on async workers
+pub fn wallets() -> Result<&'static WalletSet> {
+    WALLETS.get().ok_or_else(|| anyhow!("Wallets are not loaded"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_strategy_plans() {
+        assert_eq!(Strategy::RoundRobin.plan(3, 4, 0.3), vec![(1, 0.3)]);
+        assert_eq!(Strategy::Split(2).plan(3, 2, 1.0), vec![(2, 0.5), (0, 0.5)]);
+        assert_eq!(Strategy::Split(usize::MAX).plan(2, 0, 1.0).len(), 2);
+        assert!(Strategy::RoundRobin.plan(0, 0, 1.0).is_empty());
+
+        let set = WalletSet::new(vec![local(PrivateKeySigner::random()), local(PrivateKeySigner::random())], Strategy::RoundRobin).unwrap();
+        let first = set.plan_buy(0.1)[0].0.address();
+        let second = set.plan_buy(0.1)[0].0.address();
+        assert_ne!(first, second);
+        assert_eq!(set.plan_buy(0.1)[0].0.address(), first);
+
+        // Concurrent buys still spread evenly
+        let set = WalletSet::new((0..4).map(|_| local(PrivateKeySigner::random())).collect(), Strategy::RoundRobin).unwrap();
+        let picks: Vec<Address> = std::thread::scope(|scope| {
+            let threads: Vec<_> = (0..4).map(|_| scope.spawn(|| (0..100).map(|_| set.plan_buy(0.1)[0].0.address()).collect::<Vec<_>>())).collect();
+            threads.into_iter().flat_map(|t| t.join().unwrap()).collect()
+        });
+        for wallet in set.addresses() {
+            assert_eq!(picks.iter().filter(|p| **p == wallet).count(), 100);
+        }
+    }
+
+    #[test]
+    fn test_keystore_round_trip() {
+        let dir = env::temp_dir().join(format!("czsniper-keystore-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let signer = PrivateKeySigner::random();
+        PrivateKeySigner::encrypt_keystore(&dir, &mut rand::thread_rng(), signer.to_bytes(), "hunter2", Some("hot")).unwrap();
+        std::fs::write(dir.join(".passphrase"), "hunter2\n").unwrap();
+
+        // Dotfiles in a keystore directory are skipped, and secret files lose their newline
+        let passphrase = read_secret(&dir.join(".passphrase")).unwrap();
+        let loaded = load_keystores(std::slice::from_ref(&dir), &passphrase).unwrap();
+        assert_eq!(loaded.len(), 1);
+        assert_eq!(loaded[0].address(), signer.address());
+        assert!(load_keystores(&[dir.join("hot")], "wrong").is_err());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,150 @@
+use alloy::primitives::Address;
+use serde::{Deserialize, Serialize};
+use std::env;
+use anyhow::{Result, anyhow};
+
+use crate::{decoder::TokenCreate, targets::normalize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Kind {
+    Name,
+    Symbol,
+    Creator,
+}
+
+impl Kind {
+    pub fn parse(kind: &str) -> Result<Self> {
+        match kind {
+            "name" => Ok(Kind::Name),
+            "symbol" => Ok(Kind::Symbol),
+            "creator" => Ok(Kind::Creator),
+            other => Err(anyhow!("Unknown watchlist kind '{}'", other)),
+        }
+    }
+}
+
+// Pre-announced launches: bought as soon as they are created, tweet or no tweet
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Watchlist {
+    #[serde(default)]
+    pub names: Vec<String>,
+    #[serde(default)]
+    pub symbols: Vec<String>,
+    #[serde(default)]
+    pub creators: Vec<Address>,
+}
+
+pub fn path() -> String {
+    env::var("WATCHLIST_PATH").unwrap_or_else(|_| "watchlist.json".to_string())
+}
+
+impl Watchlist {
+    pub fn load(path: &str) -> Result<Self> {
+        match std::fs::read_to_string(path) {
+            Ok(raw) => Ok(serde_json::from_str(&raw)?),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    pub fn save(&self, path: &str) -> Result<()> {
+        std::fs::write(path, serde_json::to_string_pretty(self)?)?;
+        Ok(())
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.names.is_empty() && self.symbols.is_empty() && self.creators.is_empty()
+    }
+
+    // Returns false if the entry was already present
+    pub fn add(&mut self, kind: Kind, value: &str) -> Result<bool> {
+        match kind {
+            Kind::Creator => {
+                let creator: Address = value.parse().map_err(|_| anyhow!("'{}' is not an address", value))?;
+                Ok(push_unique(&mut self.creators, creator, |a, b| a == b))
+            }
+            Kind::Name => Ok(push_unique(&mut self.names, value.to_string(), |a, b| normalize(a) == normalize(b))),
+            Kind::Symbol => Ok(push_unique(&mut self.symbols, value.to_string(), |a, b| normalize(a) == normalize(b))),
+        }
+    }
+
+    // Returns false if there was nothing to remove
+    pub fn remove(&mut self, kind: Kind, value: &str) -> Result<bool> {
+        let before = self.len();
+        match kind {
+            Kind::Creator => {
+                let creator: Address = value.parse().map_err(|_| anyhow!("'{}' is not an address", value))?;
+                self.creators.retain(|c| *c != creator);
+            }
+            Kind::Name => self.names.retain(|n| normalize(n) != normalize(value)),
+            Kind::Symbol => self.symbols.retain(|s| normalize(s) != normalize(value)),
+        }
+        Ok(self.len() < before)
+    }
+
+    fn len(&self) -> usize {
+        self.names.len() + self.symbols.len() + self.creators.len()
+    }
+
+    // The entry the token matches, if any. Names and symbols compare ignoring case, spacing and punctuation
+    pub fn matches(&self, token: &TokenCreate) -> Option<(Kind, String)> {
+        if self.creators.contains(&token.creator) {
+            return Some((Kind::Creator, format!("{:?}", token.creator)));
+        }
+        let name = normalize(&token.name);
+        if let Some(watched) = self.names.iter().find(|n| !name.is_empty() && normalize(n) == name) {
+            return Some((Kind::Name, watched.clone()));
+        }
+        let symbol = normalize(&token.symbol);
+        self.symbols.iter()
+            .find(|s| !symbol.is_empty() && normalize(s) == symbol)
+            .map(|watched| (Kind::Symbol, watched.clone()))
+    }
+
+    pub fn describe(&self) -> String {
+        let list = |items: Vec<String>| if items.is_empty() { "-".to_string() } else { items.join(", ") };
+        format!(
+            "**Names:** {}\n**Symbols:** {}\n**Creators:** {}",
+            list(self.names.clone()),
+            list(self.symbols.clone()),
+            list(self.creators.iter().map(|c| format!("`{:?}`", c)).collect()),
+        )
+    }
+}
+
+fn push_unique<T>(items: &mut Vec<T>, value: T, same: impl Fn(&T, &T) -> bool) -> bool {
+    if items.iter().any(|existing| same(existing, &value)) {
+        return false;
+    }
+    items.push(value);
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_watchlist_matching() {
+        let mut list: Watchlist = serde_json::from_str(r#"{"names": ["Freedom of Money"], "symbols": ["BALS"]}"#).unwrap();
+        let creator = Address::repeat_byte(7);
+        assert!(list.add(Kind::Creator, &format!("{:?}", creator)).unwrap());
+        assert!(!list.add(Kind::Name, "freedom-of-money").unwrap());
+        assert!(list.add(Kind::Creator, "0x123").is_err());
+
+        let token = |name: &str, symbol: &str, creator: Address| TokenCreate {
+            creator,
+            token: Address::ZERO,
+            name: name.to_string(),
+            symbol: symbol.to_string(),
+        };
+        assert_eq!(list.matches(&token("FREEDOM OF MONEY", "FOM", Address::ZERO)), Some((Kind::Name, "Freedom of Money".to_string())));
+        assert!(list.matches(&token("币安人生", "bals", Address::ZERO)).is_some());
+        assert_eq!(list.matches(&token("Anything", "ANY", creator)).unwrap().0, Kind::Creator);
+        assert!(list.matches(&token("Freedom", "FREE", Address::ZERO)).is_none());
+
+        assert!(list.remove(Kind::Symbol, "BALS").unwrap());
+        assert!(!list.remove(Kind::Symbol, "BALS").unwrap());
+        assert!(list.matches(&token("币安人生", "bals", Address::ZERO)).is_none());
+    }
+}